@@ -16,7 +16,35 @@ pub enum OptionError {
     OptionAlreadyExercised,
     InsufficientFundsError,
     InvalidQuantityError,
-    InsufficientQuantityError
+    InsufficientQuantityError,
+    InvalidPricingInputError,
+    PremiumCapReachedError,
+    InvalidExerciseDelegateError,
+    InvalidPremiumSplitError,
+    WriteCooldownError,
+    SettlementFrozenError,
+    InvalidLotSizeError,
+    PremiumMintNotAllowed,
+    PremiumTooSmallError,
+    SettlementCapReachedError,
+    InvalidQuoteCustodyError,
+    InvalidTriggerPriceError,
+    ImpliedVolatilityError,
+    InvalidProfitCapError,
+    InvalidExerciseAmountError,
+    KeeperNotAllowedError,
+    RiskGroupCapReachedError,
+    InsufficientPayoutError,
+    InvalidFeeAccountError,
+    OptionStyleError,
+    BatchTooLargeError,
+    ExerciseTooSoonError,
+    InvalidSettlementError,
+    OpenInterestLimitError,
+    PremiumSlippageError,
+    OptionNotSettledError,
+    #[msg("Mint is not owned by the given token program")]
+    TokenProgramMismatchError
 }
 
 #[error_code]
@@ -26,7 +54,12 @@ pub enum PoolError {
     InvalidSignerBalanceError,
     InvalidCustodyTokenError,
     InvalidPoolState,
-    InvalidCustodyState
+    InvalidCustodyState,
+    DecimalsDiffTooLargeError,
+    DepositCapExceeded,
+    TotalDepositCapExceeded,
+    InitialDepositTooSmallError,
+    OptionBookPageFullError
 }
 
 #[error_code]
@@ -58,5 +91,17 @@ pub enum ContractError {
     StaleOraclePrice,
     InsufficientAmountReturned,
     TokenRatioOutOfRange,
-    CustodyAmountLimit
+    CustodyAmountLimit,
+    #[msg("Oracle price was not published within the settlement window")]
+    InvalidSettlementPrice,
+    #[msg("Oracle feeds were published too far apart")]
+    OraclePublishTimeSkew,
+    #[msg("Not enough valid oracle feeds")]
+    InsufficientOracleFeeds,
+    #[msg("Account would be left below its rent-exempt minimum")]
+    RentExemptionViolated,
+    #[msg("Oracle confidence interval is too wide")]
+    OraclePriceConfidenceError,
+    #[msg("Option sales are paused")]
+    ContractPaused
 }
\ No newline at end of file
@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::state::{Contract, Custody, Multisig, Pool};
+use crate::state::{Contract, Custody, Multisig, OracleKind, Pool};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct AddCustodyParams {
     pub oracle: Pubkey,
+    pub oracle_kind: OracleKind,
+    pub strike_exponent: i32,
+    pub volatility_bps: u16,
     pub pool_name : String
 }
 
@@ -13,6 +16,13 @@ pub fn add_custody<'info>(
     ctx: Context<'_, '_, '_, 'info, AddCustody<'info>>,
     params: &AddCustodyParams,
 ) -> Result<u8> {
+    // validate inputs, micro-priced tokens need up to 18 decimals of strike precision
+    if params.strike_exponent > 0 || params.strike_exponent < -18 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+    if params.volatility_bps == 0 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
 
     // validate signatures
     let mut multisig = ctx.accounts.multisig.load_mut()?;
@@ -33,12 +43,20 @@ pub fn add_custody<'info>(
     let pool =&mut ctx.accounts.pool;
     require_keys_eq!(*pool.custodies.last().unwrap(), ctx.accounts.custody.key());
 
+    // token amounts are valued in usd, keep the mint within the supported decimals gap
+    pool.check_decimals_diff(ctx.accounts.custody_token_mint.decimals, Contract::USD_DECIMALS)?;
+
     // record custody data
     let custody =&mut ctx.accounts.custody;
     custody.mint = ctx.accounts.custody_token_mint.key();
     custody.token_account = ctx.accounts.custody_token_account.key();
     custody.decimals = ctx.accounts.custody_token_mint.decimals;
     custody.oracle = params.oracle;
+    custody.oracle_kind = params.oracle_kind;
+    custody.strike_exponent = params.strike_exponent;
+    custody.volatility_bps = params.volatility_bps;
+    custody.premium_multiplier_bps = Contract::BPS_POWER as u64;
+    custody.collateral_buffer_bps = Contract::BPS_POWER as u64;
     
     // record bumps
     custody.bump = ctx.bumps.custody;
@@ -95,7 +113,7 @@ pub struct AddCustody<'info> {
                  custody_token_mint.key().as_ref()],
         bump
     )]
-    pub custody_token_account: Account<'info, TokenAccount>,
+    pub custody_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: empty PDA, authority for token accounts
     #[account(
@@ -104,7 +122,7 @@ pub struct AddCustody<'info> {
     )]
     pub transfer_authority: AccountInfo<'info>,
 
-    pub custody_token_mint: Box<Account<'info, Mint>>,
-    token_program: Program<'info, Token>,
+    pub custody_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 }
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, User,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AddFeeExemptParams {
+    pub user: Pubkey,
+}
+
+pub fn add_fee_exempt<'info>(
+    ctx: Context<'_, '_, '_, 'info, AddFeeExempt<'info>>,
+    params: &AddFeeExemptParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::AddFeeExempt, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update user data
+    let user = ctx.accounts.user.as_mut();
+    user.fee_exempt = true;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: AddFeeExemptParams)]
+pub struct AddFeeExempt<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", params.user.as_ref()],
+        bump,
+    )]
+    pub user: Box<Account<'info, User>>,
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Pool, TokenRatios,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AddKeeperParams {
+    pub keeper: Pubkey,
+    pub pool_name: String,
+}
+
+pub fn add_keeper<'info>(
+    ctx: Context<'_, '_, '_, 'info, AddKeeper<'info>>,
+    params: &AddKeeperParams,
+) -> Result<u8> {
+    // validate inputs
+    if ctx.accounts.pool.keepers.contains(&params.keeper) {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::AddKeeper, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update pool data
+    let pool = ctx.accounts.pool.as_mut();
+    pool.keepers.push(params.keeper);
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: AddKeeperParams)]
+pub struct AddKeeper<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        realloc = Pool::LEN + pool.custodies.len() * std::mem::size_of::<Pubkey>() +
+        pool.ratios.len() * std::mem::size_of::<TokenRatios>() +
+        pool.allowed_premium_mints.len() * std::mem::size_of::<Pubkey>() +
+        (pool.keepers.len() + 1) * std::mem::size_of::<Pubkey>(),
+        realloc::payer = signer,
+        realloc::zero = false,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    system_program: Program<'info, System>,
+}
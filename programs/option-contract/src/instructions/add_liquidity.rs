@@ -2,14 +2,15 @@
 
 use {
     crate::{
-        errors::ContractError, math, state::{
-            custody::Custody, oracle::OraclePrice, Contract, Pool
+        errors::{ContractError, PoolError}, math, state::{
+            custody::Custody, Contract, LpPosition, Pool
         }
     },
     anchor_lang::prelude::*,
     anchor_spl::{
         associated_token::AssociatedToken, 
-        token::{Mint, Token, TokenAccount},
+        token::{spl_token::native_mint, Mint, Token, TokenAccount},
+        token_interface::{self, TokenInterface},
         metadata::{
             create_metadata_accounts_v3,
             CreateMetadataAccountsV3,
@@ -27,8 +28,11 @@ pub struct AddLiquidity<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    #[account(mut)]
-    pub funding_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = funding_account.mint == custody.mint @ PoolError::InvalidCustodyTokenError
+    )]
+    pub funding_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(
         init_if_needed,
@@ -38,6 +42,15 @@ pub struct AddLiquidity<'info> {
     )]
     pub lp_token_account: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LpPosition::LEN,
+        seeds = [b"lp_position", pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+
     /// CHECK: empty PDA, authority for token accounts
     #[account(
         seeds = [b"transfer_authority"],
@@ -64,7 +77,8 @@ pub struct AddLiquidity<'info> {
         seeds = [b"custody",
                  pool.key().as_ref(),
                  custody_mint.key().as_ref()],
-        bump = custody.bump
+        bump = custody.bump,
+        constraint = custody.mint == custody_mint.key() @ PoolError::InvalidCustodyTokenError
     )]
     pub custody: Box<Account<'info, Custody>>,
 
@@ -81,7 +95,7 @@ pub struct AddLiquidity<'info> {
                  custody.mint.as_ref()],
         bump = custody.token_account_bump
     )]
-    pub custody_token_account: Box<Account<'info, TokenAccount>>,
+    pub custody_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(
         mut,
@@ -92,7 +106,7 @@ pub struct AddLiquidity<'info> {
     pub lp_token_mint: Box<Account<'info, Mint>>,
 
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub custody_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     // === METADATA ACCOUNTS (ONLY NEW ADDITION) ===
     /// CHECK: Metadata account for LP token
@@ -113,7 +127,8 @@ pub struct AddLiquidity<'info> {
     pub token_metadata_program: UncheckedAccount<'info>,
     // === END METADATA ACCOUNTS ===
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Program<'info, Token>, // lp token program
+    pub custody_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -128,6 +143,17 @@ pub struct AddLiquidityParams {
     amount_in: u64,
     min_lp_amount_out: u64,
     pool_name: String,
+    wrap_sol: bool, // deposit native lamports, wrapped into the funding account first
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub custody: Pubkey,
+    pub amount_in: u64,
+    pub fee_amount: u64,
+    pub lp_amount: u64,
 }
 
 pub fn add_liquidity<'info>(ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>, params: &AddLiquidityParams) -> Result<()> {
@@ -143,6 +169,23 @@ pub fn add_liquidity<'info>(ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'inf
     }
     // === END METADATA CREATION ===
 
+    // Native SOL is wrapped into the WSOL funding account of the owner, which already holds its
+    // rent-exempt reserve, so the account can still be closed to unwrap afterwards
+    if params.wrap_sol {
+        require_keys_eq!(
+            ctx.accounts.custody.mint,
+            native_mint::ID,
+            PoolError::InvalidCustodyTokenError
+        );
+        Contract::wrap_sol(
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.funding_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.custody_token_program.to_account_info(),
+            params.amount_in,
+        )?;
+    }
+
     let contract = ctx.accounts.contract.as_mut();
     let custody = ctx.accounts.custody.as_mut();
     let pool = ctx.accounts.pool.as_mut();
@@ -154,35 +197,58 @@ pub fn add_liquidity<'info>(ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'inf
     pool.aum_usd =
         pool.get_assets_under_management_usd(ctx.remaining_accounts, curtime)?;
 
-    let token_price = OraclePrice::new_from_oracle(
+    let token_price = contract.get_oracle_price(
         &ctx.accounts.custody_oracle_account.to_account_info(),
         curtime,
-        false,
     )?;
 
-    let fee_amount =
-        pool.get_add_liquidity_fee(token_id, params.amount_in, custody, &token_price)?;
-    msg!("Collected fee: {}", fee_amount);
-
-    let deposit_amount = math::checked_sub(params.amount_in, fee_amount)?;
-
     // transfer tokens
     msg!("Transfer tokens");
+    let custody_balance_before = ctx.accounts.custody_token_account.amount;
     contract.transfer_tokens_from_user(
         ctx.accounts.funding_account.to_account_info(),
         ctx.accounts.custody_token_account.to_account_info(),
+        &ctx.accounts.custody_mint,
         ctx.accounts.owner.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.custody_token_program.to_account_info(),
         params.amount_in,
     )?;
 
+    // Token-2022 transfer fees are withheld from the transfer, so the deposit is booked and
+    // priced at the amount the custody token account actually received
+    ctx.accounts.custody_token_account.reload()?;
+    let amount_received = math::checked_sub(
+        ctx.accounts.custody_token_account.amount,
+        custody_balance_before,
+    )?;
+    require_gte!(
+        amount_received,
+        1u64,
+        ContractError::InsufficientAmountReturned
+    );
+
+    // Deposits are capped per liquidity provider and in total while the pool is capacity constrained
+    let deposit_usd = token_price.get_asset_amount_usd(amount_received, custody.decimals)?;
+    if let Some(capacity_usd) = pool.get_deposit_capacity_usd(pool.aum_usd) {
+        require_gte!(capacity_usd, deposit_usd as u128, PoolError::TotalDepositCapExceeded);
+    }
+    let lp_position = ctx.accounts.lp_position.as_mut();
+    lp_position.owner = ctx.accounts.owner.key();
+    lp_position.pool = pool.key();
+    lp_position.bump = ctx.bumps.lp_position;
+    lp_position.record_deposit(deposit_usd, pool.max_deposit_per_lp_usd)?;
+
+    let fee_amount =
+        pool.get_add_liquidity_fee(token_id, amount_received, custody, &token_price)?;
+    msg!("Collected fee: {}", fee_amount);
+
     // compute assets under management
     msg!("Compute assets under management");
     let pool_amount_usd =
         pool.get_assets_under_management_usd(ctx.remaining_accounts, curtime)?;
 
     // compute amount of lp tokens to mint
-    let no_fee_amount = math::checked_sub(params.amount_in, fee_amount)?;
+    let no_fee_amount = math::checked_sub(amount_received, fee_amount)?;
     require_gte!(
         no_fee_amount,
         1u64,
@@ -191,18 +257,17 @@ pub fn add_liquidity<'info>(ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'inf
 
     let token_amount_usd = token_price.get_asset_amount_usd(no_fee_amount, custody.decimals)?;
 
-    let lp_amount = if pool_amount_usd == 0 {
-        token_amount_usd
-    } else {
-        math::checked_as_u64(math::checked_div(
-            math::checked_mul(
-                token_amount_usd as u128,
-                ctx.accounts.lp_token_mint.supply as u128,
-            )?,
-            pool_amount_usd,
-        )?)?
-    };
+    let lp_amount = Pool::get_lp_amount(
+        token_amount_usd,
+        ctx.accounts.lp_token_mint.supply,
+        pool_amount_usd,
+    )?;
     msg!("LP tokens to mint: {}", lp_amount);
+    require_gte!(
+        lp_amount,
+        params.min_lp_amount_out.max(1),
+        ContractError::InsufficientAmountReturned
+    );
 
     // mint lp tokens
     contract.mint_tokens(
@@ -212,7 +277,8 @@ pub fn add_liquidity<'info>(ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'inf
         ctx.accounts.token_program.to_account_info(),
         lp_amount,
     )?;
-    custody.token_owned = math::checked_add(custody.token_owned, deposit_amount)?;
+    // the fee stays in the custody, so the whole received amount is owned by the pool
+    custody.credit_deposit(amount_received)?;
 
     // update pool stats
     msg!("Update pool stats");
@@ -220,6 +286,15 @@ pub fn add_liquidity<'info>(ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'inf
     pool.aum_usd =
         pool.get_assets_under_management_usd(ctx.remaining_accounts, curtime)?;
 
+    emit!(LiquidityAdded {
+        owner: ctx.accounts.owner.key(),
+        pool: pool.key(),
+        custody: custody.key(),
+        amount_in: amount_received,
+        fee_amount,
+        lp_amount,
+    });
+
     Ok(())
 }
 
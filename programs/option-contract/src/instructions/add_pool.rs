@@ -38,6 +38,7 @@ pub fn add_pool<'info>(ctx: Context<'_, '_, '_, 'info, AddPool<'info>>, params:
     }
 
     pool.name = params.name.clone();
+    pool.max_decimals_diff = Pool::DEFAULT_MAX_DECIMALS_DIFF;
     pool.bump = ctx.bumps.pool;
     pool.lp_token_bump = ctx.bumps.lp_token_mint;
     contract.pools.push(ctx.accounts.pool.key());
@@ -1,12 +1,12 @@
 use crate::{
     errors::OptionError,
     math,
-    state::{Contract, Custody, OptionDetail, OraclePrice, Pool, User},
+    state::{Contract, Custody, OptionBookPage, OptionDetail, OraclePrice, Pool, User},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -16,16 +16,34 @@ pub struct AutoExerciseOptionParams {
     pub pool_name: String,
 }
 
-pub fn auto_exercise(
-    ctx: Context<AutoExerciseOption>,
+#[event]
+pub struct OptionExpired {
+    pub option_index: u64,
+    pub owner: Pubkey,
+    pub strike_price: u64, // scaled by strike_exponent
+    pub strike_exponent: i32,
+    pub premium: u64,
+    pub amount: u64,
+    pub is_call: bool,
+    pub expired_date: i64,
+    pub profit: u64, // owed to the holder, net of reserve_fee
+    pub reserve_fee: u64,
+}
+
+// remaining accounts: the backup oracles of the custody with their settlement prints, see
+// Custody::get_settlement_backup_len
+pub fn auto_exercise<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AutoExerciseOption<'info>>,
     params: &AutoExerciseOptionParams,
 ) -> Result<()> {
     let option_detail = &mut ctx.accounts.option_detail;
     let contract = &ctx.accounts.contract;
+    let pool = &mut ctx.accounts.pool;
     let user = &mut ctx.accounts.user;
     let custody: &mut Box<Account<'_, Custody>> = &mut ctx.accounts.custody;
     let locked_custody = &mut ctx.accounts.locked_custody;
-    let locked_oracle = &ctx.accounts.locked_oracle;
+    let custody_oracle = &ctx.accounts.custody_oracle;
+    let settlement_oracle = &ctx.accounts.settlement_oracle;
 
     // ✅ CRITICAL VALIDATION CHECKS - Add these at the beginning
     require_gte!(user.option_index, params.option_index);
@@ -42,6 +60,12 @@ pub fn auto_exercise(
         option_detail.valid,
         OptionError::OptionNotValid
     );
+
+    // Disputed options can't be settled until admins unfreeze them
+    require!(
+        !option_detail.settlement_frozen,
+        OptionError::SettlementFrozenError
+    );
     
     // ✅ Verify option belongs to the specified user
     require_eq!(
@@ -50,6 +74,12 @@ pub fn auto_exercise(
         OptionError::InvalidOwner
     );
 
+    // Only keepers on the allowlist of the pool may settle, anyone while it is empty
+    require!(
+        pool.is_keeper_allowed(&ctx.accounts.tester.key()),
+        OptionError::KeeperNotAllowedError
+    );
+
     // Current Unix timestamp
     let current_timestamp = contract.get_time()?;
 
@@ -60,52 +90,169 @@ pub fn auto_exercise(
         OptionError::InvalidTimeError
     );
 
-    let token_price =
-        OraclePrice::new_from_oracle(locked_oracle, current_timestamp, false)?;
-    let oracle_price = token_price.get_price();
+    // In-house accounts don't fund the reserve
+    let reserve_bps = if user.fee_exempt {
+        0
+    } else {
+        contract.auto_exercise_reserve_bps
+    };
+
+    settle_expired_option(
+        option_detail,
+        pool,
+        &mut ctx.accounts.option_book_page,
+        custody,
+        locked_custody,
+        custody_oracle,
+        settlement_oracle,
+        ctx.remaining_accounts,
+        &ctx.accounts.locked_oracle,
+        reserve_bps,
+        current_timestamp,
+    )?;
+    if !option_detail.valid {
+        user.remove_open_option();
+    }
+
+    Ok(())
+}
+
+/// Settles an expired option of `pool`, shared by auto_exercise and auto_exercise_batch.
+/// For calls `custody` and `locked_custody` are two views of the same account, changes to it
+/// are made on `locked_custody`, which has to be written back last.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn settle_expired_option<'info>(
+    option_detail: &mut Account<'info, OptionDetail>,
+    pool: &mut Pool,
+    option_book_page: &mut OptionBookPage,
+    custody: &mut Account<'info, Custody>,
+    locked_custody: &mut Account<'info, Custody>,
+    custody_oracle: &AccountInfo<'info>,
+    settlement_oracle: &AccountInfo<'info>,
+    backup_accounts: &[AccountInfo<'info>],
+    locked_oracle: &AccountInfo<'info>,
+    reserve_bps: u64,
+    current_timestamp: i64,
+) -> Result<()> {
+    // Averaged settlement mode settles on the reads recorded around expiry once the window
+    // is over, otherwise against the first prints after expiry, not the spot at call time
+    if custody.settlement_window_sec > 0 {
+        require_gt!(
+            current_timestamp,
+            math::checked_add(option_detail.expired_date, custody.settlement_window_sec)?,
+            OptionError::InvalidTimeError
+        );
+    }
+    let token_price = match option_detail.get_expiry_price(
+        custody,
+        custody_oracle,
+        settlement_oracle,
+        backup_accounts,
+    ) {
+        Ok(token_price) => token_price,
+        Err(err) => {
+            // The feed published nothing since expiry and the settlement window is over,
+            // defer settlement to the first print after the feed recovers
+            let settlement_deadline =
+                OraclePrice::get_settlement_deadline(option_detail.expired_date, false)?;
+            if !option_detail.pending_settlement
+                && current_timestamp > settlement_deadline
+                && OraclePrice::get_publish_time(custody_oracle)? < option_detail.expired_date
+            {
+                msg!("Oracle is unavailable since expiry, deferring settlement");
+                option_detail.pending_settlement = true;
+                return Ok(());
+            }
+            return Err(err);
+        }
+    };
+    option_detail.pending_settlement = false;
+
+    // ✅ Mark option as exercised and invalid before its profit is booked, a manual exercise
+    // racing this settlement fails on the exercised check
+    option_detail.exercised = current_timestamp as u64;
+    option_detail.valid = false;
+    pool.remove_open_option(option_book_page, &option_detail.key());
 
     require_gte!(
         locked_custody.token_locked,
         option_detail.amount,
         OptionError::InvalidLockedBalanceError
     );
+    // payouts are proportional to the contract size
+    require_gt!(option_detail.quantity, 0, OptionError::InvalidQuantityError);
 
-    if custody.key() == locked_custody.key() {
-        // call option - only exercise if profitable
-        if oracle_price > option_detail.strike_price {
-            // Calculate Sol Amount from Option Detail Value : call / covered sol
-            let amount = (oracle_price - option_detail.strike_price) * (option_detail.quantity as f64) / oracle_price;
-
-            option_detail.profit = amount as u64;
-            option_detail.claimed = amount as u64;
-        } else {
-            // Option expired out of the money - no profit
-            option_detail.claimed = 0;
-            option_detail.profit = 0;
-        }
+    // At the money expires worthless, see OptionDetail::is_in_the_money
+    let in_the_money = option_detail.is_in_the_money(&token_price);
+    // Locked asset is valued at a fresh print published after expiry
+    let locked_price = OraclePrice::new_settlement_leg_price(
+        locked_oracle,
+        current_timestamp,
+        option_detail.expired_date,
+    )?;
+    // Profit in locked tokens through the same integer pipeline as exercise_option, calls
+    // are paid out at the settlement price of the underlying itself
+    let payout_price = if custody.key() == locked_custody.key() {
+        token_price
     } else {
-        // put option - only exercise if profitable
-        if option_detail.strike_price > oracle_price {
-            // Calculate Profit amount with option detail values: put / cash-secured usdc
-            let amount = (option_detail.strike_price - oracle_price) * (option_detail.quantity as f64);
-
-            option_detail.profit = amount as u64;
-            option_detail.claimed = amount as u64;
-        } else {
-            // Option expired out of the money - no profit
-            option_detail.claimed = 0;
-            option_detail.profit = 0;
-        }
+        locked_price
+    };
+    let amount = if in_the_money {
+        option_detail.get_exercise_profit(
+            &token_price,
+            &payout_price,
+            custody.decimals,
+            locked_custody.decimals,
+        )?
+    } else {
+        // Option expired out of the money - no profit
+        0
+    };
+    option_detail.profit = amount;
+    option_detail.claimed = amount;
+
+    let mut reserve_fee = 0;
+    if option_detail.claimed > 0 {
+        pool.record_payout(
+            locked_price.get_asset_amount_usd(option_detail.claimed, locked_custody.decimals)?,
+        )?;
+
+        // Part of the profit funds the reserve, rounded down in favour of the holder
+        reserve_fee = math::checked_as_u64(math::checked_div(
+            math::checked_mul(option_detail.claimed as u128, reserve_bps as u128)?,
+            Contract::BPS_POWER,
+        )?)?;
+        option_detail.claimed = math::checked_sub(option_detail.claimed, reserve_fee)?;
+        option_detail.profit = option_detail.claimed;
+        locked_custody.add_reserve(reserve_fee)?;
+        locked_custody.add_claim(option_detail.claimed)?;
     }
 
-    // ✅ Mark option as exercised and invalid
-    option_detail.exercised = current_timestamp as u64;
-    option_detail.valid = false;
+    // custody is written back before locked_custody, record on the latter when they alias
+    let settled_custody: &mut Custody = if custody.key() == locked_custody.key() {
+        locked_custody
+    } else {
+        custody
+    };
+    settled_custody.record_settlement_price(option_detail.expired_date, token_price)?;
 
     // ✅ Update locked custody balance
     locked_custody.token_locked =
         math::checked_sub(locked_custody.token_locked, option_detail.amount)?;
 
+    emit!(OptionExpired {
+        option_index: option_detail.index,
+        owner: option_detail.owner,
+        strike_price: option_detail.strike_price,
+        strike_exponent: option_detail.strike_exponent,
+        premium: option_detail.premium,
+        amount: option_detail.amount,
+        is_call: option_detail.option_type == 0,
+        expired_date: option_detail.expired_date,
+        profit: option_detail.profit,
+        reserve_fee,
+    });
+
     Ok(())
 }
 
@@ -130,10 +277,10 @@ pub struct AutoExerciseOption<'info> {
 
     // ✅ CRITICAL FIX: MOVE ALL MINTS TO TOP BEFORE DEPENDENT ACCOUNTS
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(mut)]
-    pub locked_custody_mint: Box<Account<'info, Mint>>,
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
 
     // ✅ NOW these accounts can derive correctly with mints available
     #[account(
@@ -145,7 +292,17 @@ pub struct AutoExerciseOption<'info> {
     )]
     pub custody: Box<Account<'info, Custody>>, // Target price asset
 
+    /// CHECK: oracle account for the position token
     #[account(
+        constraint = custody_oracle.key() == custody.oracle
+    )]
+    pub custody_oracle: AccountInfo<'info>,
+
+    /// CHECK: price update of the custody oracle feed published at expiry, validated on settlement
+    pub settlement_oracle: AccountInfo<'info>,
+
+    #[account(
+        mut,
         seeds = [b"user", params.user.key().as_ref()],
         bump,
     )]
@@ -161,6 +318,15 @@ pub struct AutoExerciseOption<'info> {
     )]
     pub option_detail: Box<Account<'info, OptionDetail>>,
 
+    #[account(
+        mut,
+        seeds = [b"option_book",
+                 pool.key().as_ref(),
+                 option_detail.book_page.to_le_bytes().as_ref()],
+        bump = option_book_page.bump
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+
     #[account(
         mut,
         seeds = [b"custody",
@@ -177,7 +343,7 @@ pub struct AutoExerciseOption<'info> {
     )]
     pub locked_oracle: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
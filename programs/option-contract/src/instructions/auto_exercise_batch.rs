@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::{OptionError, PoolError},
+    instructions::{settle_expired_option, AutoExerciseOptionParams},
+    math,
+    state::{Contract, Custody, OptionBookPage, OptionDetail, Pool, User},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AutoExerciseBatchParams {
+    pub pool_name: String,
+    pub options: Vec<AutoExerciseOptionParams>,
+}
+
+#[derive(Copy, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
+pub struct CustodySettlement {
+    pub custody: Pubkey, // locked custody, amounts are in its tokens
+    pub payout: u64,     // owed to the holders, net of reserve fees
+    pub collateral_freed: u64,
+}
+
+#[event]
+pub struct BatchSettled {
+    pub pool: Pubkey,
+    pub settled: u64,
+    pub skipped: u64, // not ready or deferred until the oracle recovers
+    pub custodies: Vec<CustodySettlement>,
+}
+
+impl BatchSettled {
+    fn new(pool: Pubkey) -> Self {
+        Self {
+            pool,
+            settled: 0,
+            skipped: 0,
+            custodies: Vec::new(),
+        }
+    }
+
+    // Totals are kept per locked custody, token amounts of different mints don't add up
+    fn record_settlement(
+        &mut self,
+        custody: Pubkey,
+        payout: u64,
+        collateral_freed: u64,
+    ) -> Result<()> {
+        self.settled = math::checked_add(self.settled, 1)?;
+        let index = match self.custodies.iter().position(|c| c.custody == custody) {
+            Some(index) => index,
+            None => {
+                self.custodies.push(CustodySettlement {
+                    custody,
+                    ..Default::default()
+                });
+                self.custodies.len() - 1
+            }
+        };
+        let totals = &mut self.custodies[index];
+        totals.payout = math::checked_add(totals.payout, payout)?;
+        totals.collateral_freed = math::checked_add(totals.collateral_freed, collateral_freed)?;
+        Ok(())
+    }
+
+    fn record_skip(&mut self) -> Result<()> {
+        self.skipped = math::checked_add(self.skipped, 1)?;
+        Ok(())
+    }
+}
+
+// user, option_detail, option_book_page, custody, custody_oracle, settlement_oracle,
+// locked_custody, locked_oracle
+const ACCOUNTS_PER_OPTION: usize = 8;
+
+// Settle several expired options of the pool, options not ready for settlement are skipped.
+// remaining accounts: ACCOUNTS_PER_OPTION accounts per option, in the order of params.options,
+// each followed by the backup oracles of its custody with their settlement prints, see
+// Custody::get_settlement_backup_len
+pub fn auto_exercise_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AutoExerciseBatch<'info>>,
+    params: &AutoExerciseBatchParams,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    require!(
+        params.options.len() <= OptionDetail::MAX_AUTO_EXERCISE_BATCH,
+        OptionError::BatchTooLargeError
+    );
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        params.options.len() * ACCOUNTS_PER_OPTION,
+        OptionError::InvalidOptionIndexError
+    );
+
+    // Only keepers on the allowlist of the pool may settle, anyone while it is empty
+    require!(
+        pool.is_keeper_allowed(&ctx.accounts.keeper.key()),
+        OptionError::KeeperNotAllowedError
+    );
+
+    let current_timestamp = ctx.accounts.contract.get_time()?;
+
+    let mut summary = BatchSettled::new(pool.key());
+    let mut remaining_accounts = ctx.remaining_accounts;
+    for option in &params.options {
+        let Some((
+            [
+                user_info,
+                option_detail_info,
+                option_book_page_info,
+                custody_info,
+                custody_oracle,
+                settlement_oracle,
+                locked_custody_info,
+                locked_oracle,
+            ],
+            accounts,
+        )) = remaining_accounts.split_first_chunk::<ACCOUNTS_PER_OPTION>()
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys.into());
+        };
+
+        let mut option_detail = Account::<OptionDetail>::try_from(option_detail_info)?;
+        require!(option.pool_name == params.pool_name, PoolError::InvalidPoolState);
+        require_keys_eq!(option_detail.pool, pool.key(), PoolError::InvalidPoolState);
+        require_keys_eq!(option_detail.owner, option.user, OptionError::InvalidOwner);
+        require_eq!(
+            option_detail.index,
+            option.option_index,
+            OptionError::InvalidOptionIndexError
+        );
+
+        // The backup feeds of the custody follow, skipped options have to pass them as well
+        require_keys_eq!(
+            custody_info.key(),
+            option_detail.custody,
+            PoolError::InvalidCustodyState
+        );
+        let mut custody = Account::<Custody>::try_from(custody_info)?;
+        let backup_len = custody.get_settlement_backup_len();
+        if accounts.len() < backup_len {
+            return Err(ProgramError::NotEnoughAccountKeys.into());
+        }
+        let (backup_accounts, accounts) = accounts.split_at(backup_len);
+        remaining_accounts = accounts;
+
+        // Options settled, frozen or not expired yet are left for a later run
+        if option_detail.exercised != 0
+            || !option_detail.valid
+            || option_detail.settlement_frozen
+            || current_timestamp < option_detail.expired_date
+        {
+            msg!(
+                "Option {} of {} is not ready, skipping",
+                option.option_index,
+                option.user
+            );
+            summary.record_skip()?;
+            continue;
+        }
+
+        require_keys_eq!(
+            locked_custody_info.key(),
+            option_detail.locked_asset,
+            PoolError::InvalidCustodyState
+        );
+        let mut locked_custody = Account::<Custody>::try_from(locked_custody_info)?;
+        // pages are only written by the program, their pool and index identify them
+        let mut option_book_page = Account::<OptionBookPage>::try_from(option_book_page_info)?;
+        require!(
+            option_book_page.pool == pool.key()
+                && option_book_page.index == option_detail.book_page,
+            PoolError::InvalidPoolState
+        );
+        require_keys_eq!(custody_oracle.key(), custody.oracle);
+        require_keys_eq!(locked_oracle.key(), locked_custody.oracle);
+
+        // In-house accounts don't fund the reserve
+        let (user_key, _) =
+            Pubkey::find_program_address(&[b"user", option.user.as_ref()], &crate::ID);
+        require_keys_eq!(user_info.key(), user_key, OptionError::InvalidOwner);
+        let mut user = Account::<User>::try_from(user_info)?;
+        let reserve_bps = if user.fee_exempt {
+            0
+        } else {
+            ctx.accounts.contract.auto_exercise_reserve_bps
+        };
+
+        if custody.settlement_window_sec > 0
+            && current_timestamp
+                <= option_detail.expired_date.saturating_add(custody.settlement_window_sec)
+        {
+            msg!(
+                "Option {} of {} is not ready, skipping",
+                option.option_index,
+                option.user
+            );
+            summary.record_skip()?;
+            continue;
+        }
+
+        settle_expired_option(
+            &mut option_detail,
+            pool,
+            &mut option_book_page,
+            &mut custody,
+            &mut locked_custody,
+            custody_oracle,
+            settlement_oracle,
+            backup_accounts,
+            locked_oracle,
+            reserve_bps,
+            current_timestamp,
+        )?;
+
+        // custody first, locked_custody holds the changes when both are the same account
+        option_detail.exit(&crate::ID)?;
+        option_book_page.exit(&crate::ID)?;
+        custody.exit(&crate::ID)?;
+        locked_custody.exit(&crate::ID)?;
+
+        if option_detail.valid {
+            // deferred until the oracle publishes again
+            summary.record_skip()?;
+        } else {
+            user.remove_open_option();
+            user.exit(&crate::ID)?;
+            summary.record_settlement(
+                locked_custody.key(),
+                option_detail.claimed,
+                option_detail.amount,
+            )?;
+        }
+    }
+    require!(
+        remaining_accounts.is_empty(),
+        OptionError::InvalidOptionIndexError
+    );
+    msg!(
+        "Settled {} of {} options",
+        summary.settled,
+        params.options.len()
+    );
+    emit!(summary);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: AutoExerciseBatchParams)]
+pub struct AutoExerciseBatch<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_summary_totals_settlements_per_locked_custody() {
+        let pool = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let wsol = Pubkey::new_unique();
+
+        let mut summary = BatchSettled::new(pool);
+        summary.record_settlement(usdc, 150, 1_000).unwrap();
+        summary.record_skip().unwrap();
+        summary.record_settlement(wsol, 0, 2_000_000_000).unwrap();
+        summary.record_settlement(usdc, 50, 3_000).unwrap();
+
+        assert_eq!(summary.pool, pool);
+        assert_eq!(summary.settled, 3);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(
+            summary.custodies,
+            vec![
+                CustodySettlement {
+                    custody: usdc,
+                    payout: 200,
+                    collateral_freed: 4_000,
+                },
+                CustodySettlement {
+                    custody: wsol,
+                    payout: 0,
+                    collateral_freed: 2_000_000_000,
+                },
+            ]
+        );
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -15,6 +15,14 @@ pub struct ClaimOptionParams {
     pub pool_name: String
 }
 
+#[event]
+pub struct OptionClaimed {
+    pub option_index: u64,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub forfeited: bool, // claim window was over, the amount reverted to the pool
+}
+
 pub fn claim_option(ctx: Context<ClaimOption>, params: &ClaimOptionParams) -> Result<()> {
     let token_program = &ctx.accounts.token_program;
     let option_detail = &mut ctx.accounts.option_detail;
@@ -42,29 +50,56 @@ pub fn claim_option(ctx: Context<ClaimOption>, params: &ClaimOptionParams) -> Re
     // ✅ Must have claimable amount
     require_gt!(option_detail.claimed, 0);
 
+    // Profit left unclaimed past the claim window reverts to the pool
+    let curtime = contract.get_time()?;
+    if locked_custody.is_claim_window_over(option_detail.exercised as i64, curtime)? {
+        msg!("Claim window is over, {} reverts to the pool", option_detail.claimed);
+        emit!(OptionClaimed {
+            option_index: option_detail.index,
+            owner: option_detail.owner,
+            amount: option_detail.claimed,
+            forfeited: true,
+        });
+        locked_custody.settle_claim(option_detail.claimed);
+        option_detail.claimed = 0;
+        option_detail.forfeited = true;
+        return Ok(());
+    }
+
+    // Claims past the settlement cap of the epoch are paid in a later epoch
+    require!(
+        locked_custody.try_consume_settlement_cap(option_detail.claimed, curtime)?,
+        OptionError::SettlementCapReachedError
+    );
+
     // ✅ Check custody has enough available tokens (owned - locked)
     require_gte!(
         math::checked_sub(locked_custody.token_owned, locked_custody.token_locked)?, 
         option_detail.claimed
     );
 
-    // ✅ Update custody balance
-    locked_custody.token_owned = math::checked_sub(locked_custody.token_owned, option_detail.claimed)?;
-    
-    // ✅ Set profit and reset claimed
-    option_detail.profit = option_detail.claimed;
+    // ✅ Update custody balance, reset claimed, profit already includes it
     let claim_amount = option_detail.claimed;
     option_detail.claimed = 0;
+    locked_custody.pay_claim(claim_amount)?;
 
     // ✅ FIXED: Use actual custody token account, not oracle
     contract.transfer_tokens(
         locked_custody_token_account.to_account_info(),
         funding_account.to_account_info(),
+        &ctx.accounts.locked_custody_mint,
         transfer_authority.to_account_info(),
         token_program.to_account_info(),
         claim_amount,
     )?;
 
+    emit!(OptionClaimed {
+        option_index: option_detail.index,
+        owner: option_detail.owner,
+        amount: claim_amount,
+        forfeited: false,
+    });
+
     Ok(())
 }
 
@@ -78,7 +113,7 @@ pub struct ClaimOption<'info> {
         mut,
         has_one = owner
     )]
-    pub funding_account: Box<Account<'info, TokenAccount>>,
+    pub funding_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: empty PDA, authority for token accounts
     #[account(
@@ -102,10 +137,10 @@ pub struct ClaimOption<'info> {
 
     // ✅ CRITICAL FIX: MOVE ALL MINTS TO TOP BEFORE DEPENDENT ACCOUNTS
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(mut)]
-    pub locked_custody_mint: Box<Account<'info, Mint>>,
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         seeds = [b"user", owner.key().as_ref()],
@@ -152,7 +187,7 @@ pub struct ClaimOption<'info> {
         constraint = locked_custody_token_account.mint == locked_custody_mint.key() @ OptionError::InvalidMintError,
         constraint = funding_account.mint == locked_custody_mint.key() @ OptionError::InvalidMintError
     )]
-    pub locked_custody_token_account: Box<Account<'info, TokenAccount>>,
+    pub locked_custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: oracle account for the position token
     #[account(
@@ -160,7 +195,7 @@ pub struct ClaimOption<'info> {
     )]
     pub locked_oracle: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
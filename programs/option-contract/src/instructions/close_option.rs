@@ -1,12 +1,12 @@
 use crate::{
-    errors::OptionError,
+    errors::{ContractError, OptionError},
     math,
-    state::{Contract, Custody, OptionDetail, OraclePrice, Pool, User},
+    state::{Contract, Custody, OptionBookPage, OptionDetail, Pool, User},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -16,13 +16,47 @@ pub struct CloseOptionParams {
     pub close_quantity: u64,  // Number of option contracts to close
 }
 
-pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Result<()> {
+#[event]
+pub struct OptionClosed {
+    pub option_index: u64,
+    pub owner: Pubkey,
+    pub closed_quantity: u64,
+    pub unlocked_amount: u64,
+    pub refund_amount: u64,
+    pub remaining_quantity: u64,
+}
+
+/// Backup oracles of `custody` and of `locked_custody` among `remaining_accounts`, in that
+/// order, shared by close_option and close_limit_option. A call locks its own custody and
+/// shares its backup oracles.
+pub(crate) fn split_backup_oracles<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    custody: &Account<'info, Custody>,
+    locked_custody: &Account<'info, Custody>,
+) -> (&'a [AccountInfo<'info>], &'a [AccountInfo<'info>]) {
+    let backup_len = custody
+        .get_backup_oracle_len()
+        .min(remaining_accounts.len());
+    let (backup_oracles, rest) = remaining_accounts.split_at(backup_len);
+    if locked_custody.key() == custody.key() {
+        (backup_oracles, backup_oracles)
+    } else {
+        (backup_oracles, rest)
+    }
+}
+
+// remaining accounts: the backup oracles of the custody, then those of the locked custody when
+// it is another custody, see Custody::get_backup_oracle_len
+pub fn close_option<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseOption<'info>>,
+    params: &CloseOptionParams,
+) -> Result<()> {
     let token_program = &ctx.accounts.token_program;
     let option_detail = &mut ctx.accounts.option_detail;
     let closed_option_detail = &mut ctx.accounts.closed_option_detail;
     let contract = &ctx.accounts.contract;
-    let user = &ctx.accounts.user;
-    let pool = &ctx.accounts.pool;
+    let user = &mut ctx.accounts.user;
+    let pool = &mut ctx.accounts.pool;
     let custody = &ctx.accounts.custody;
     let transfer_authority = &ctx.accounts.transfer_authority;
 
@@ -34,6 +68,9 @@ pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Re
     let custody_oracle_account = &ctx.accounts.custody_oracle_account;
     let locked_oracle = &ctx.accounts.locked_oracle;
 
+    // Buybacks halt in an emergency, exercises and withdrawals stay open for users to exit
+    require!(!contract.paused, ContractError::ContractPaused);
+
     require_keys_eq!(pay_custody.key(), option_detail.premium_asset);
     require_keys_eq!(locked_custody.key(), option_detail.locked_asset);
     require_gte!(user.option_index, params.option_index);
@@ -69,35 +106,47 @@ pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Re
         let remaining_years = remaining_days / 365.0;
 
         // Oracle price of underlying asset (SOL)
-        let underlying_price = OraclePrice::new_from_oracle(
-            custody_oracle_account,
-            current_time,
-            false,
-        )?.get_price();
-
-        // Recalculate current option value using Black-Scholes for full position
-        let bs_price_per_contract = OptionDetail::black_scholes(
+        let (backup_oracles, locked_backup_oracles) =
+            split_backup_oracles(ctx.remaining_accounts, custody, locked_custody);
+        let underlying_price = custody
+            .get_oracle_price(
+                contract,
+                current_time,
+                custody_oracle_account,
+                backup_oracles,
+            )?
+            .get_price();
+
+        // Recalculate current option value using Black-Scholes for full position, capped
+        // options are valued as the spread they were sold as
+        let bs_price_per_contract = OptionDetail::get_capped_option_price(
+            custody.pricing_model,
             underlying_price,
-            option_detail.strike_price,
+            &option_detail.get_strike_price(),
             remaining_years,
             option_detail.option_type == 0, // 0 = call, 1 = put
-        );
+            custody.volatility_bps,
+            option_detail.profit_cap,
+        )?;
 
         // Calculate proportional premium for close quantity
         let bs_price_partial = bs_price_per_contract * params.close_quantity as f64;
 
         // Get locked token oracle price for USD to locked token conversion
-        let locked_token_price = OraclePrice::new_from_oracle(
-            locked_oracle,
+        let locked_token_price = locked_custody.get_oracle_price(
+            contract,
             current_time,
-            false,
-        )?.get_price();
+            locked_oracle,
+            locked_backup_oracles,
+        )?;
 
-        // Convert USD option value to locked token amount using float math (like original code)
+        // Convert USD option value to locked token amount in fixed point
         let token_decimals = locked_custody.decimals;
-        let refund_amount_raw = math::checked_as_u64(
-            math::checked_float_div(bs_price_partial, locked_token_price)?
-                * math::checked_powi(10.0, token_decimals as i32)?
+        let refund_amount_raw = OptionDetail::get_buyback_amount(
+            bs_price_per_contract,
+            params.close_quantity,
+            &locked_token_price,
+            token_decimals,
         )?;
 
         // Debug logging to see actual values
@@ -105,10 +154,14 @@ pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Re
         msg!("Quantity partial price: {}", params.close_quantity);
         msg!("Quantity full price: {}", option_detail.quantity);
         msg!("Black-Scholes partial price: {}", bs_price_partial);
-        msg!("Locked token price: {}", locked_token_price);
+        msg!(
+            "Locked token price: {}, exponent: {}",
+            locked_token_price.price,
+            locked_token_price.exponent
+        );
         msg!("Token decimals: {}", token_decimals);
         msg!("Refund amount raw: {}", refund_amount_raw);
-        msg!("Strike price: {}", option_detail.strike_price);
+        msg!("Strike price: {}, exponent: {}", option_detail.strike_price, option_detail.strike_exponent);
         msg!("Current underlying price: {}", underlying_price);
         msg!("Option type (0=call, 1=put): {}", option_detail.option_type);
         msg!("Remaining years: {}", remaining_years);
@@ -127,6 +180,15 @@ pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Re
         // Apply 10% platform fee (90% refund)
         let refund_amount = math::checked_div(math::checked_mul(refund_amount_raw, 9)?, 10)?;
 
+        // A capped position never refunds more than its payout cap for the closed quantity
+        let refund_amount = match option_detail.max_profit {
+            Some(max_profit) => refund_amount.min(math::checked_div(
+                math::checked_mul(max_profit, params.close_quantity)?,
+                option_detail.quantity,
+            )?),
+            None => refund_amount,
+        };
+
         // Check locked custody has enough balance for refund
         require_gte!(
             math::checked_sub(locked_custody.token_owned, locked_custody.token_locked)?,
@@ -142,11 +204,23 @@ pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Re
         contract.transfer_tokens(
             locked_custody_token_account.to_account_info(),
             funding_account.to_account_info(),
+            &ctx.accounts.locked_custody_mint,
             transfer_authority.to_account_info(),
             token_program.to_account_info(),
             refund_amount,
         )?;
 
+        // The profit cap shrinks with the position
+        if let Some(max_profit) = option_detail.max_profit {
+            option_detail.max_profit = Some(math::checked_div(
+                math::checked_mul(
+                    max_profit,
+                    math::checked_sub(option_detail.quantity, params.close_quantity)?,
+                )?,
+                option_detail.quantity,
+            )?);
+        }
+
         // Update original position (reduce by closed amount)
         option_detail.quantity = math::checked_sub(option_detail.quantity, params.close_quantity)?;
         option_detail.amount = math::checked_sub(option_detail.amount, unlock_amount)?;
@@ -177,9 +251,15 @@ pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Re
             closed_option_detail.period = option_detail.period;
             closed_option_detail.expired_date = option_detail.expired_date;
             closed_option_detail.purchase_date = option_detail.purchase_date;
+            closed_option_detail.created_slot = option_detail.created_slot;
             closed_option_detail.option_type = option_detail.option_type;
             closed_option_detail.strike_price = option_detail.strike_price;
+            closed_option_detail.strike_exponent = option_detail.strike_exponent;
+            closed_option_detail.breakeven_price = option_detail.breakeven_price;
             closed_option_detail.premium_asset = option_detail.premium_asset;
+            closed_option_detail.is_american = option_detail.is_american;
+            closed_option_detail.settlement = option_detail.settlement;
+            closed_option_detail.profit_cap = option_detail.profit_cap;
             closed_option_detail.locked_asset = option_detail.locked_asset;
             closed_option_detail.pool = pool.key();
             closed_option_detail.custody = custody.key();
@@ -194,7 +274,18 @@ pub fn close_option(ctx: Context<CloseOption>, params: &CloseOptionParams) -> Re
         if option_detail.quantity == 0 {
             option_detail.valid = false;
             option_detail.bought_back = current_time as u64;
+            pool.remove_open_option(&mut ctx.accounts.option_book_page, &option_detail.key());
+            user.remove_open_option();
         }
+
+        emit!(OptionClosed {
+            option_index: option_detail.index,
+            owner: option_detail.owner,
+            closed_quantity: params.close_quantity,
+            unlocked_amount: unlock_amount,
+            refund_amount,
+            remaining_quantity: option_detail.quantity,
+        });
     }
 
     Ok(())
@@ -212,7 +303,7 @@ pub struct CloseOption<'info> {
         constraint = funding_account.mint == locked_custody.mint,
         has_one = owner
     )]
-    pub funding_account: Box<Account<'info, TokenAccount>>,
+    pub funding_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: empty PDA, authority for token accounts
     #[account(
@@ -236,6 +327,7 @@ pub struct CloseOption<'info> {
     pub pool: Box<Account<'info, Pool>>,
 
     #[account(
+        mut,
         seeds = [b"user", owner.key().as_ref()],
         bump,
     )]
@@ -243,11 +335,11 @@ pub struct CloseOption<'info> {
 
     // ✅ MOVE MINTS TO TOP
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
-    pub pay_custody_mint: Box<Account<'info, Mint>>,
+    pub pay_custody_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
-    pub locked_custody_mint: Box<Account<'info, Mint>>,
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -285,7 +377,7 @@ pub struct CloseOption<'info> {
         bump,
         constraint = locked_custody_token_account.mint == locked_custody_mint.key()
     )]
-    pub locked_custody_token_account: Box<Account<'info, TokenAccount>>,
+    pub locked_custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -296,6 +388,15 @@ pub struct CloseOption<'info> {
     )]
     pub option_detail: Box<Account<'info, OptionDetail>>,
 
+    #[account(
+        mut,
+        seeds = [b"option_book",
+                 pool.key().as_ref(),
+                 option_detail.book_page.to_le_bytes().as_ref()],
+        bump = option_book_page.bump
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+
     #[account(
         init_if_needed,
         payer = owner,
@@ -320,7 +421,7 @@ pub struct CloseOption<'info> {
     #[account(constraint = locked_oracle.key() == locked_custody.oracle)]
     pub locked_oracle: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
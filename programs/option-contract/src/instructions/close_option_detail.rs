@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::OptionError,
+    instructions::OptionAccountClosed,
+    state::OptionDetail,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CloseOptionDetailParams {
+    pub option_index: u64,
+}
+
+// Close a settled option of the owner and refund its rent
+pub fn close_option_detail(
+    ctx: Context<CloseOptionDetail>,
+    params: &CloseOptionDetailParams,
+) -> Result<()> {
+    let option_detail = &ctx.accounts.option_detail;
+
+    // Live options and profit still to be claimed keep their account
+    require!(
+        option_detail.is_closable(),
+        OptionError::OptionNotSettledError
+    );
+
+    emit!(OptionAccountClosed {
+        option_index: params.option_index,
+        owner: ctx.accounts.owner.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: CloseOptionDetailParams)]
+pub struct CloseOptionDetail<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ OptionError::InvalidOwner,
+        constraint = option_detail.index == params.option_index @ OptionError::InvalidOptionIndexError,
+        close = owner
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::OptionError, state::OptionDetail};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CloseOptionsBatchParams {
+    pub option_indices: Vec<u64>,
+}
+
+#[event]
+pub struct OptionAccountClosed {
+    pub option_index: u64,
+    pub owner: Pubkey,
+}
+
+// Close settled options of the owner and refund their rent, live or unclaimed ones are skipped.
+// remaining accounts: the OptionDetail of each of option_indices, in the same order
+pub fn close_options_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseOptionsBatch<'info>>,
+    params: &CloseOptionsBatchParams,
+) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+
+    require!(
+        params.option_indices.len() <= OptionDetail::MAX_CLOSE_BATCH,
+        OptionError::InvalidOptionIndexError
+    );
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        params.option_indices.len(),
+        OptionError::InvalidOptionIndexError
+    );
+
+    let mut closed = 0;
+    for (account_info, &option_index) in ctx.remaining_accounts.iter().zip(&params.option_indices) {
+        let option_detail = Account::<OptionDetail>::try_from(account_info)?;
+        require_keys_eq!(option_detail.owner, owner.key(), OptionError::InvalidOwner);
+        require_eq!(option_detail.index, option_index, OptionError::InvalidOptionIndexError);
+
+        // Live options and profit still to be claimed keep their account
+        if !option_detail.is_closable() {
+            msg!("Option {} is not settled, skipping", option_index);
+            continue;
+        }
+
+        option_detail.close(owner.to_account_info())?;
+        emit!(OptionAccountClosed {
+            option_index,
+            owner: owner.key(),
+        });
+        closed += 1;
+    }
+    msg!("Closed {} of {} options", closed, params.option_indices.len());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseOptionsBatch<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
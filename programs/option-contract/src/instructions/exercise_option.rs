@@ -1,24 +1,100 @@
 use crate::{
     errors::OptionError,
     math,
-    state::{Contract, Custody, OptionDetail, OraclePrice, Pool, User},
+    state::{
+        Contract, Custody, OptionBookPage, OptionDetail, OraclePrice, Pool, SettlementKind, User,
+    },
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct ExerciseOptionParams {
     pub option_index: u64,
-    pub pool_name: String
+    pub pool_name: String,
+    pub exercise_amount: u64, // part of the locked amount to exercise, the whole amount settles the option
+    pub min_tokens_out: u64, // min payout in the locked token, 0 = any
 }
 
-pub fn exercise_option(ctx: Context<ExerciseOption>, params: &ExerciseOptionParams) -> Result<()> {
+#[event]
+pub struct OptionDelivered {
+    pub option_index: u64,
+    pub owner: Pubkey,
+    pub quantity: u64,
+    pub delivered_amount: u64, // underlying sent to the holder, in locked tokens
+    pub strike_payment: u64, // paid by the holder, in quote tokens
+}
+
+#[event]
+pub struct OptionExercised {
+    pub option_index: u64,
+    pub owner: Pubkey,
+    pub strike_price: u64, // scaled by strike_exponent
+    pub strike_exponent: i32,
+    pub premium: u64,
+    pub amount: u64,
+    pub is_call: bool,
+    pub expired_date: i64,
+    pub profit: u64,
+}
+
+// Transfers of a mint have to go through the token program that owns it
+fn check_mint_token_program(mint: &AccountInfo, token_program: &Pubkey) -> Result<()> {
+    require_keys_eq!(
+        *mint.owner,
+        *token_program,
+        OptionError::TokenProgramMismatchError
+    );
+    Ok(())
+}
+
+/// Cash payout of exercising `exercise_amount` of the collateral of a call or put: its share
+/// of the profit at `oracle_price` in locked tokens, net of the early exercise penalty.
+#[allow(clippy::too_many_arguments)]
+fn get_cash_payout(
+    option_detail: &OptionDetail,
+    locked_custody: &Custody,
+    oracle_price: &OraclePrice,
+    locked_price: &OraclePrice,
+    custody_decimals: u8,
+    exercise_amount: u64,
+    min_tokens_out: u64,
+    remaining_sec: i64,
+    tenor_sec: i64,
+) -> Result<u64> {
+    // calls pay (oracle_price - strike_price) * quantity, puts the opposite
+    let payout = math::checked_as_u64(math::checked_div(
+        math::checked_mul(
+            option_detail.get_exercise_profit(
+                oracle_price,
+                locked_price,
+                custody_decimals,
+                locked_custody.decimals,
+            )? as u128,
+            exercise_amount as u128,
+        )?,
+        option_detail.amount as u128,
+    )?)?;
+    let payout = math::checked_sub(
+        payout,
+        locked_custody.get_early_exercise_penalty(payout, remaining_sec, tenor_sec)?,
+    )?;
+    // The holder bounds the payout against an adverse conversion into the locked token
+    require_gte!(payout, min_tokens_out, OptionError::InsufficientPayoutError);
+    Ok(payout)
+}
+
+pub fn exercise_option<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExerciseOption<'info>>,
+    params: &ExerciseOptionParams,
+) -> Result<()> {
     let token_program = &ctx.accounts.token_program;
     let option_detail = &mut ctx.accounts.option_detail;
     let contract = &ctx.accounts.contract;
+    let pool = &mut ctx.accounts.pool;
     let user = &mut ctx.accounts.user;
     let funding_account = &mut ctx.accounts.funding_account;
     let transfer_authority = &mut ctx.accounts.transfer_authority;
@@ -28,6 +104,11 @@ pub fn exercise_option(ctx: Context<ExerciseOption>, params: &ExerciseOptionPara
     let locked_oracle = &ctx.accounts.locked_oracle;
     let custody_oracle = &ctx.accounts.custody_oracle;
 
+    check_mint_token_program(
+        &ctx.accounts.locked_custody_mint.to_account_info(),
+        &token_program.key(),
+    )?;
+
     // ✅ CRITICAL VALIDATION CHECKS - Add these at the beginning
     require_gte!(user.option_index, params.option_index);
     
@@ -43,8 +124,14 @@ pub fn exercise_option(ctx: Context<ExerciseOption>, params: &ExerciseOptionPara
         option_detail.valid,
         OptionError::OptionNotValid
     );
+
+    // Disputed options can't be settled until admins unfreeze them
+    require!(
+        !option_detail.settlement_frozen,
+        OptionError::SettlementFrozenError
+    );
     
-    // ✅ Verify option belongs to caller
+    // ✅ Verify option belongs to owner
     require_eq!(
         option_detail.owner,
         ctx.accounts.owner.key(),
@@ -54,111 +141,294 @@ pub fn exercise_option(ctx: Context<ExerciseOption>, params: &ExerciseOptionPara
     // Current Unix timestamp
     let current_timestamp = contract.get_time()?;
 
-    // Check if option is available to exercise, before expired time.
+    // Exercise window: american options are exercisable here while now < expired_date,
+    // european ones never are. At and after expired_date every option, american or european,
+    // settles through auto_exercise only.
+    require!(option_detail.is_american, OptionError::OptionStyleError);
     require_gt!(
         option_detail.expired_date,
         current_timestamp as i64,
         OptionError::InvalidTimeError
     );
 
+    // An option can't be written and exercised around a manipulated price in the same bundle
+    let current_slot = contract.get_slot()?;
+    require_gte!(
+        current_slot.saturating_sub(option_detail.created_slot),
+        contract.min_exercise_slot_gap,
+        OptionError::ExerciseTooSoonError
+    );
+
+    // Both feeds are used together for puts, their prints must be close in time
+    if locked_oracle.key() != custody_oracle.key() {
+        OraclePrice::check_publish_time_skew(
+            locked_oracle,
+            custody_oracle,
+            contract.max_publish_time_skew_sec,
+        )?;
+    }
+
     let token_price =
-        OraclePrice::new_from_oracle(locked_oracle, current_timestamp, false)?;
-    let sol_price =
-        OraclePrice::new_from_oracle(custody_oracle, current_timestamp, false)?;
-    let oracle_price = sol_price.get_price();
+        contract.get_oracle_price(locked_oracle, current_timestamp)?;
+    // remaining accounts are backup oracles of the custody for a median price
+    let sol_price = custody.get_oracle_price(
+        contract,
+        current_timestamp,
+        custody_oracle,
+        ctx.remaining_accounts,
+    )?;
+
+    // Owner or their exercise delegate can exercise, see User::can_receive_payout for
+    // which token account the payout may go to. Once the take-profit trigger is crossed
+    // anyone may exercise as long as the payout goes to the owner.
+    let triggered = option_detail.is_trigger_crossed(&sol_price)
+        && funding_account.owner == ctx.accounts.owner.key();
+    require!(
+        triggered
+            || user.can_receive_payout(
+                &ctx.accounts.owner.key(),
+                &ctx.accounts.authority.key(),
+                &funding_account.owner,
+            ),
+        OptionError::InvalidExerciseDelegateError
+    );
 
     require_gte!(
         locked_custody.token_locked,
         option_detail.amount,
         OptionError::InvalidLockedBalanceError
     );
+    // payouts are proportional to the contract size
+    require_gt!(option_detail.quantity, 0, OptionError::InvalidQuantityError);
 
-    if custody.key() == locked_custody.key() {
-        // call option
+    // Options can be exercised in parts, each part settles its share of the position
+    require!(
+        params.exercise_amount > 0 && params.exercise_amount <= option_detail.amount,
+        OptionError::InvalidExerciseAmountError
+    );
+    let exercise_quantity = if params.exercise_amount == option_detail.amount {
+        option_detail.quantity
+    } else {
+        math::checked_as_u64(math::checked_div(
+            math::checked_mul(option_detail.quantity as u128, params.exercise_amount as u128)?,
+            option_detail.amount as u128,
+        )?)?
+    };
+    // Parts are prorated in integers rounding down and the last part takes the remainder,
+    // so the parts sum exactly to the position. A part too small to carry a unit is rejected.
+    require_gt!(exercise_quantity, 0, OptionError::InvalidExerciseAmountError);
+
+    // At the money options have nothing to exercise, see OptionDetail::is_in_the_money
+    require!(
+        option_detail.is_in_the_money(&sol_price),
+        OptionError::InvalidPriceRequirementError
+    );
+
+    // Early exercise gives up time value, part of the payout is kept by the pool
+    let remaining_sec = math::checked_sub(option_detail.expired_date, current_timestamp)?;
+    let tenor_sec =
+        math::checked_sub(option_detail.expired_date, option_detail.purchase_date as i64)?;
+
+    // Physically settled calls pay the strike into the quote custody of the pool
+    let strike_accounts = if option_detail.settlement == SettlementKind::Physical {
+        let (
+            Some(strike_payment_account),
+            Some(strike_custody),
+            Some(strike_custody_token_account),
+            Some(strike_custody_mint),
+            Some(strike_token_program),
+        ) = (
+            ctx.accounts.strike_payment_account.as_ref(),
+            ctx.accounts.strike_custody.as_mut(),
+            ctx.accounts.strike_custody_token_account.as_ref(),
+            ctx.accounts.strike_custody_mint.as_ref(),
+            ctx.accounts.strike_token_program.as_ref(),
+        )
+        else {
+            return err!(OptionError::InvalidSettlementError);
+        };
+        require_keys_eq!(
+            strike_custody.key(),
+            pool.quote_custody,
+            OptionError::InvalidSettlementError
+        );
+        require_keys_eq!(
+            strike_custody_token_account.key(),
+            strike_custody.token_account
+        );
+        require_keys_eq!(
+            strike_custody_mint.key(),
+            strike_custody.mint,
+            OptionError::InvalidMintError
+        );
+        // the quote mint may live under another token program than the locked mint
+        check_mint_token_program(
+            &strike_custody_mint.to_account_info(),
+            &strike_token_program.key(),
+        )?;
+        Some((
+            strike_payment_account,
+            strike_custody,
+            strike_custody_token_account,
+            strike_custody_mint,
+            strike_token_program,
+        ))
+    } else {
+        None
+    };
+
+    let exercised_profit;
+    let mut payout_usd = None;
+    let mut strike_payment = 0;
+    if let Some((strike_payment_account, strike_custody, _, _, _)) = strike_accounts.as_ref() {
+        // physical call: the holder receives the underlying of the exercised options, at a
+        // full strike payment the pool keeps no early exercise penalty
+        let delivered_amount =
+            OptionDetail::get_delivery_amount(exercise_quantity, locked_custody.decimals)?;
+        require_gte!(
+            params.exercise_amount,
+            delivered_amount,
+            OptionError::InvalidLockedBalanceError
+        );
+        require_gte!(
+            delivered_amount,
+            params.min_tokens_out,
+            OptionError::InsufficientPayoutError
+        );
+
+        strike_payment = option_detail.get_strike_value(exercise_quantity, strike_custody.decimals)?;
         require_gte!(
-            oracle_price,
-            option_detail.strike_price,
-            OptionError::InvalidPriceRequirementError
+            strike_payment_account.amount,
+            strike_payment,
+            OptionError::InvalidSignerBalanceError
         );
-        
-        // Calculate profit amount for call option: (oracle_price - strike_price) * quantity
-        // Using safe decimal math to handle precision properly
-        let price_diff = math::checked_as_u64(oracle_price - option_detail.strike_price)?;
-        let amount = math::checked_decimal_mul(
-            price_diff,
-            0, // oracle price exponent (assuming normalized)
-            option_detail.quantity,
-            0, // quantity exponent 
-            -(custody.decimals as i32), // target token decimals
-        )?;        
-
-        // Use raw oracle price data instead of converted f64 to avoid precision loss
-        require_gt!(token_price.price, 0, OptionError::InvalidPriceRequirementError);
-        
-        let profit_per_unit = math::checked_decimal_div(
-            amount,
-            -(custody.decimals as i32), // amount is already in target decimals
-            token_price.price,
-            token_price.exponent,
-            -(custody.decimals as i32), // keep same precision
+
+        // the pool pays out the underlying net of the strike it receives
+        payout_usd = Some(
+            token_price
+                .get_asset_amount_usd(delivered_amount, locked_custody.decimals)?
+                .saturating_sub(
+                    option_detail.get_strike_value(exercise_quantity, Contract::USD_DECIMALS)?,
+                ),
+        );
+        exercised_profit = delivered_amount;
+    } else {
+        exercised_profit = get_cash_payout(
+            option_detail,
+            locked_custody,
+            &sol_price,
+            &token_price,
+            custody.decimals,
+            params.exercise_amount,
+            params.min_tokens_out,
+            remaining_sec,
+            tenor_sec,
         )?;
+    }
+
+    option_detail.profit = math::checked_add(option_detail.profit, exercised_profit)?;
+    let payout_usd = match payout_usd {
+        Some(payout_usd) => payout_usd,
+        None => token_price.get_asset_amount_usd(exercised_profit, locked_custody.decimals)?,
+    };
+    pool.record_payout(payout_usd)?;
+
+    // custody is written back before locked_custody, record on the latter when they alias
+    let settled_custody: &mut Custody = if custody.key() == locked_custody.key() {
+        locked_custody
+    } else {
+        custody
+    };
+    settled_custody.record_settlement_price(current_timestamp, sol_price)?;
+
+    // ✅ Update locked custody balance
+    locked_custody.token_locked =
+        math::checked_sub(locked_custody.token_locked, params.exercise_amount)?;
+    // The profit cap shrinks with the position
+    if let Some(max_profit) = option_detail.max_profit {
+        option_detail.max_profit = Some(math::checked_div(
+            math::checked_mul(
+                max_profit,
+                math::checked_sub(option_detail.amount, params.exercise_amount)?,
+            )?,
+            option_detail.amount,
+        )?);
+    }
+    option_detail.amount = math::checked_sub(option_detail.amount, params.exercise_amount)?;
+    option_detail.quantity = math::checked_sub(option_detail.quantity, exercise_quantity)?;
+
+    // ✅ Mark option as exercised and invalid once nothing is left to exercise
+    if option_detail.amount == 0 {
+        option_detail.exercised = current_timestamp as u64;
+        option_detail.valid = false;
+        pool.remove_open_option(&mut ctx.accounts.option_book_page, &option_detail.key());
+        user.remove_open_option();
+    }
+
+    // Tokens only leave the custody once the position is updated (checks-effects-interactions).
+    // Physical delivery is all or nothing, cash payouts past the settlement cap of the epoch
+    // are queued as a claim.
+    if let Some((
+        strike_payment_account,
+        strike_custody,
+        strike_custody_token_account,
+        strike_custody_mint,
+        strike_token_program,
+    )) = strike_accounts
+    {
+        contract.transfer_tokens_from_user(
+            strike_payment_account.to_account_info(),
+            strike_custody_token_account.to_account_info(),
+            strike_custody_mint,
+            ctx.accounts.authority.to_account_info(),
+            strike_token_program.to_account_info(),
+            strike_payment,
+        )?;
+        strike_custody.token_owned = math::checked_add(strike_custody.token_owned, strike_payment)?;
 
-        // ✅ FIXED: Use the custody token account instead of custody metadata account
         contract.transfer_tokens(
             locked_custody_token_account.to_account_info(),
             funding_account.to_account_info(),
+            &ctx.accounts.locked_custody_mint,
             transfer_authority.to_account_info(),
             token_program.to_account_info(),
-            profit_per_unit,
-        )?;
-
-        option_detail.profit = profit_per_unit;
-    } else {
-        require_gte!(
-            option_detail.strike_price,
-            oracle_price,
-            OptionError::InvalidPriceRequirementError
-        );
-
-        // Calculate profit amount for put option: (strike_price - oracle_price) * quantity
-        // Using safe decimal math to handle precision properly
-        let price_diff = math::checked_as_u64(option_detail.strike_price - oracle_price)?;
-        let amount = math::checked_decimal_mul(
-            price_diff,
-            0, // oracle price exponent (assuming normalized)
-            option_detail.quantity,
-            0, // quantity exponent
-            -(custody.decimals as i32), // target token decimals
-        )?;
-        require_gt!(token_price.price, 0, OptionError::InvalidPriceRequirementError);
-        
-        let profit_per_unit = math::checked_decimal_div(
-            amount,
-            -(custody.decimals as i32), // amount is already in target decimals
-            token_price.price,
-            token_price.exponent,
-            -(locked_custody.decimals as i32), // keep same precision
+            exercised_profit,
         )?;
+        locked_custody.token_owned =
+            math::checked_sub(locked_custody.token_owned, exercised_profit)?;
 
+        emit!(OptionDelivered {
+            option_index: option_detail.index,
+            owner: option_detail.owner,
+            quantity: exercise_quantity,
+            delivered_amount: exercised_profit,
+            strike_payment,
+        });
+    } else if locked_custody.book_payout(exercised_profit, current_timestamp)? {
         // ✅ FIXED: Use the custody token account instead of custody metadata account
         contract.transfer_tokens(
             locked_custody_token_account.to_account_info(),
             funding_account.to_account_info(),
+            &ctx.accounts.locked_custody_mint,
             transfer_authority.to_account_info(),
             token_program.to_account_info(),
-            profit_per_unit,
+            exercised_profit,
         )?;
-
-        option_detail.profit = profit_per_unit;
+    } else {
+        option_detail.claimed = math::checked_add(option_detail.claimed, exercised_profit)?;
     }
 
-    // ✅ Mark option as exercised and invalid (these changes will now be saved!)
-    option_detail.exercised = current_timestamp as u64;
-    option_detail.valid = false;
-
-    // ✅ Update locked custody balance
-    locked_custody.token_locked =
-        math::checked_sub(locked_custody.token_locked, option_detail.amount)?;
+    emit!(OptionExercised {
+        option_index: option_detail.index,
+        owner: option_detail.owner,
+        strike_price: option_detail.strike_price,
+        strike_exponent: option_detail.strike_exponent,
+        premium: option_detail.premium,
+        amount: option_detail.amount,
+        is_call: option_detail.option_type == 0,
+        expired_date: option_detail.expired_date,
+        profit: option_detail.profit,
+    });
 
     Ok(())
 }
@@ -166,14 +436,15 @@ pub fn exercise_option(ctx: Context<ExerciseOption>, params: &ExerciseOptionPara
 #[derive(Accounts)]
 #[instruction(params: ExerciseOptionParams)]
 pub struct ExerciseOption<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    /// CHECK: option holder
+    pub owner: AccountInfo<'info>,
 
-    #[account(
-        mut,
-        has_one = owner
-    )]
-    pub funding_account: Box<Account<'info, TokenAccount>>,
+    // owner or exercise delegate of the owner, any keeper once the trigger is crossed
+    pub authority: Signer<'info>,
+
+    // receives the payout, owned by the owner or an allowed delegate
+    #[account(mut)]
+    pub funding_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: empty PDA, authority for token accounts
     #[account(
@@ -197,10 +468,10 @@ pub struct ExerciseOption<'info> {
 
     // ✅ CRITICAL FIX: MOVE ALL MINTS TO TOP BEFORE DEPENDENT ACCOUNTS
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(mut)]
-    pub locked_custody_mint: Box<Account<'info, Mint>>,
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
 
     // ✅ NOW these accounts can derive correctly with mints available
     #[account(
@@ -213,6 +484,7 @@ pub struct ExerciseOption<'info> {
     pub custody: Box<Account<'info, Custody>>, // Target price asset
 
     #[account(
+        mut,
         seeds = [b"user", owner.key().as_ref()],
         bump,
     )]
@@ -228,6 +500,15 @@ pub struct ExerciseOption<'info> {
     )]
     pub option_detail: Box<Account<'info, OptionDetail>>,
 
+    #[account(
+        mut,
+        seeds = [b"option_book",
+                 pool.key().as_ref(),
+                 option_detail.book_page.to_le_bytes().as_ref()],
+        bump = option_book_page.bump
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+
     #[account(
         mut,
         seeds = [b"custody",
@@ -247,7 +528,7 @@ pub struct ExerciseOption<'info> {
         bump,
         constraint = locked_custody_token_account.mint == locked_custody_mint.key() @ OptionError::InvalidMintError,
     )]
-    pub locked_custody_token_account: Box<Account<'info, TokenAccount>>,
+    pub locked_custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: oracle account for the position token
     #[account(
@@ -261,7 +542,86 @@ pub struct ExerciseOption<'info> {
     )]
     pub custody_oracle: AccountInfo<'info>,
 
-    pub token_program: Program<'info, Token>,
+    // quote token account the strike is paid from, only required for physical settlement
+    #[account(mut)]
+    pub strike_payment_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 strike_custody.mint.as_ref()],
+        bump = strike_custody.bump
+    )]
+    pub strike_custody: Option<Box<Account<'info, Custody>>>, // quote custody of the pool
+
+    #[account(mut)]
+    pub strike_custody_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub strike_custody_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    // token program of the quote mint, only required for physical settlement
+    pub strike_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cash_payouts_are_prorated_and_bounded_by_min_tokens_out() {
+        // 2 puts struck at 150 lock 300 USDC, half of them exercised at 120
+        let option_detail = OptionDetail {
+            quantity: 2,
+            strike_price: 15_000,
+            strike_exponent: -2,
+            option_type: 1,
+            amount: 300_000_000,
+            ..Default::default()
+        };
+        let locked_custody = Custody {
+            decimals: 6,
+            ..Default::default()
+        };
+        let payout = |min_tokens_out: u64| {
+            get_cash_payout(
+                &option_detail,
+                &locked_custody,
+                &OraclePrice::new(12_000_000_000, -8),
+                &OraclePrice::new(100_000_000, -8),
+                9,
+                150_000_000,
+                min_tokens_out,
+                0,
+                86_400,
+            )
+        };
+
+        assert_eq!(payout(0).unwrap(), 30_000_000);
+        assert!(payout(30_000_001).is_err());
+    }
+
+    #[test]
+    fn mints_are_checked_against_their_own_token_program() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![];
+        let mint = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &anchor_spl::token_2022::ID,
+            false,
+            0,
+        );
+
+        assert!(check_mint_token_program(&mint, &anchor_spl::token_2022::ID).is_ok());
+        assert!(check_mint_token_program(&mint, &anchor_spl::token::ID).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, OptionDetail, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct FreezeOptionParams {
+    pub owner: Pubkey,
+    pub option_index: u64,
+    pub pool_name: String,
+}
+
+pub fn freeze_option<'info>(
+    ctx: Context<'_, '_, '_, 'info, FreezeOption<'info>>,
+    params: &FreezeOptionParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::FreezeOption, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // block exercise and auto exercise of this option only
+    let option_detail = ctx.accounts.option_detail.as_mut();
+    option_detail.settlement_frozen = true;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: FreezeOptionParams)]
+pub struct FreezeOption<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+
+    #[account(
+        mut,
+        seeds = [b"option", params.owner.as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+}
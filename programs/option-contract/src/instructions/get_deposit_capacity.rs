@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Contract, Pool};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetDepositCapacityParams {
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct DepositCapacity {
+    pub aum_usd: u128,
+    pub max_total_deposits_usd: u64, // 0 = no cap
+    pub remaining_usd: Option<u128>, // None - uncapped
+}
+
+// remaining accounts: the custodies of the pool followed by their oracles, as for the AUM
+pub fn get_deposit_capacity<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetDepositCapacity<'info>>,
+    _params: &GetDepositCapacityParams,
+) -> Result<DepositCapacity> {
+    let pool = &ctx.accounts.pool;
+    let curtime = ctx.accounts.contract.get_time()?;
+    let aum_usd = pool.get_assets_under_management_usd(ctx.remaining_accounts, curtime)?;
+
+    Ok(DepositCapacity {
+        aum_usd,
+        max_total_deposits_usd: pool.max_total_deposits_usd,
+        remaining_usd: pool.get_deposit_capacity_usd(aum_usd),
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetDepositCapacityParams)]
+pub struct GetDepositCapacity<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
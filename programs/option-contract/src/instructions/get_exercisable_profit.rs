@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{
+    errors::OptionError,
+    state::{Contract, Custody, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetExercisableProfitParams {
+    pub user: Pubkey,
+    pub option_index: u64,
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct ExercisableProfit {
+    pub theoretical_profit: u64,  // profit at the current oracle price, in locked tokens
+    pub available_liquidity: u64, // balance of the locked custody token account
+    pub max_profit: u64,          // min(theoretical_profit, available_liquidity)
+}
+
+pub fn get_exercisable_profit(
+    ctx: Context<GetExercisableProfit>,
+    _params: &GetExercisableProfitParams,
+) -> Result<ExercisableProfit> {
+    let option_detail = &ctx.accounts.option_detail;
+    let custody = &ctx.accounts.custody;
+    let locked_custody = &ctx.accounts.locked_custody;
+    let available_liquidity = ctx.accounts.locked_custody_token_account.amount;
+
+    let curtime = ctx.accounts.contract.get_time()?;
+
+    // exercised, bought back, expired or european options can't be exercised
+    if !option_detail.valid
+        || option_detail.exercised != 0
+        || !option_detail.is_american
+        || option_detail.expired_date <= curtime
+    {
+        return Ok(ExercisableProfit {
+            available_liquidity,
+            ..Default::default()
+        });
+    }
+
+    let contract = &ctx.accounts.contract;
+    let token_price = contract.get_oracle_price(&ctx.accounts.locked_oracle, curtime)?;
+    let custody_price = contract.get_oracle_price(&ctx.accounts.custody_oracle, curtime)?;
+
+    let theoretical_profit = option_detail.get_exercise_profit(
+        &custody_price,
+        &token_price,
+        custody.decimals,
+        locked_custody.decimals,
+    )?;
+    let max_profit = std::cmp::min(theoretical_profit, available_liquidity);
+    if max_profit < theoretical_profit {
+        msg!(
+            "Payout capped by pool liquidity: {} < {}",
+            available_liquidity,
+            theoretical_profit
+        );
+    }
+
+    Ok(ExercisableProfit {
+        theoretical_profit,
+        available_liquidity,
+        max_profit,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetExercisableProfitParams)]
+pub struct GetExercisableProfit<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody_mint.key().as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>, // Target price asset
+
+    #[account(
+        seeds = [b"option", params.user.as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump,
+        constraint = option_detail.locked_asset == locked_custody.key() @ OptionError::InvalidMintError
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 locked_custody_mint.key().as_ref()],
+        bump = locked_custody.bump
+    )]
+    pub locked_custody: Box<Account<'info, Custody>>,
+
+    #[account(
+        seeds = [b"custody_token_account",
+                 pool.key().as_ref(),
+                 locked_custody_mint.key().as_ref()],
+        bump
+    )]
+    pub locked_custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: oracle account for the locked token
+    #[account(
+        constraint = locked_oracle.key() == locked_custody.oracle
+    )]
+    pub locked_oracle: AccountInfo<'info>,
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle.key() == custody.oracle
+    )]
+    pub custody_oracle: AccountInfo<'info>,
+}
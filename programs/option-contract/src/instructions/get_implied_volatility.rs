@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    math,
+    state::{Contract, Custody, OptionDetail, OraclePrice, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetImpliedVolatilityParams {
+    pub strike: u64,       // scaled by the custody strike exponent
+    pub expired_time: i64, // Unix epoch time
+    pub is_call: bool,
+    pub premium: u64,      // market premium per unit in usd, USD_DECIMALS
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct ImpliedVolatility {
+    pub volatility_bps: u64, // annualized
+}
+
+pub fn get_implied_volatility(
+    ctx: Context<GetImpliedVolatility>,
+    params: &GetImpliedVolatilityParams,
+) -> Result<ImpliedVolatility> {
+    let custody = &ctx.accounts.custody;
+    let curtime = ctx.accounts.contract.get_time()?;
+    let token_price =
+        ctx.accounts.contract.get_oracle_price(&ctx.accounts.custody_oracle_account, curtime)?;
+
+    let remaining_years = math::checked_float_div(
+        math::checked_sub(params.expired_time, curtime)? as f64,
+        365.0 * 86400.0,
+    )?;
+    let strike_price = OraclePrice::new(params.strike, custody.strike_exponent).checked_as_f64()?;
+    let premium =
+        OraclePrice::new(params.premium, -(Contract::USD_DECIMALS as i32)).checked_as_f64()?;
+
+    let volatility = OptionDetail::implied_volatility(
+        token_price.get_price(),
+        strike_price,
+        remaining_years,
+        params.is_call,
+        premium,
+    )?;
+
+    Ok(ImpliedVolatility {
+        volatility_bps: math::checked_as_u64(volatility * Contract::BPS_POWER as f64)?,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetImpliedVolatilityParams)]
+pub struct GetImpliedVolatility<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>, // Target price asset
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle_account.key() == custody.oracle
+    )]
+    pub custody_oracle_account: AccountInfo<'info>,
+}
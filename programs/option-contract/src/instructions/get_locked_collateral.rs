@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::PoolError,
+    math,
+    state::{Contract, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetLockedCollateralParams {
+    pub strike_bucket_size: f64, // width of a strike bucket in usd
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct StrikeBucket {
+    pub strike_price: f64, // lower bound of the bucket
+    pub call_locked: u64,
+    pub put_locked: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct LockedCollateral {
+    pub call_locked: u64,
+    pub put_locked: u64,
+    pub strike_buckets: Vec<StrikeBucket>,
+}
+
+pub fn get_locked_collateral<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetLockedCollateral<'info>>,
+    params: &GetLockedCollateralParams,
+) -> Result<LockedCollateral> {
+    // validate inputs
+    if !params.strike_bucket_size.is_finite() || params.strike_bucket_size <= 0.0 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    let pool_key = ctx.accounts.pool.key();
+    let mut breakdown = LockedCollateral::default();
+
+    for account_info in ctx.remaining_accounts.iter() {
+        let option_detail = Account::<OptionDetail>::try_from(account_info)?;
+        require_keys_eq!(option_detail.pool, pool_key, PoolError::InvalidPoolState);
+
+        // only open options lock collateral
+        if !option_detail.valid {
+            continue;
+        }
+
+        let strike_price = (option_detail.get_strike_price().checked_as_f64()?
+            / params.strike_bucket_size)
+            .floor()
+            * params.strike_bucket_size;
+        let bucket_idx = match breakdown
+            .strike_buckets
+            .iter()
+            .position(|bucket| bucket.strike_price == strike_price)
+        {
+            Some(idx) => idx,
+            None => {
+                breakdown.strike_buckets.push(StrikeBucket {
+                    strike_price,
+                    ..Default::default()
+                });
+                breakdown.strike_buckets.len() - 1
+            }
+        };
+        let bucket = &mut breakdown.strike_buckets[bucket_idx];
+
+        if option_detail.option_type == 0 {
+            breakdown.call_locked = math::checked_add(breakdown.call_locked, option_detail.amount)?;
+            bucket.call_locked = math::checked_add(bucket.call_locked, option_detail.amount)?;
+        } else {
+            breakdown.put_locked = math::checked_add(breakdown.put_locked, option_detail.amount)?;
+            bucket.put_locked = math::checked_add(bucket.put_locked, option_detail.amount)?;
+        }
+    }
+
+    Ok(breakdown)
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetLockedCollateralParams)]
+pub struct GetLockedCollateral<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+    // remaining accounts:
+    //   option detail accounts of the pool (read-only, unsigned)
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::{OptionError, PoolError},
+    state::{Contract, OptionBookPage, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetOpenOptionsParams {
+    pub pool_name: String,
+    pub page: u32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct OpenOptionSummary {
+    pub option: Pubkey,
+    pub owner: Pubkey,
+    pub custody: Pubkey,
+    pub strike_price: u64, // scaled by strike_exponent
+    pub strike_exponent: i32,
+    pub amount: u64,
+    pub expired_date: i64,
+    pub is_call: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct OpenOptionsPage {
+    pub options: Vec<OpenOptionSummary>,
+    pub total: u64, // open options in the pool
+}
+
+// Page `params.page` of the open options of the pool, Pool::OPEN_OPTIONS_PAGE_SIZE per page,
+// read from the option book page holding it, see OptionBookPage::get_book_page.
+// remaining accounts: the OptionDetail accounts of the page, in option book order
+pub fn get_open_options<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetOpenOptions<'info>>,
+    params: &GetOpenOptionsParams,
+) -> Result<OpenOptionsPage> {
+    let pool = &ctx.accounts.pool;
+    let page_keys = ctx.accounts.option_book_page.get_view_page(params.page);
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        page_keys.len(),
+        OptionError::InvalidOptionIndexError
+    );
+
+    let mut options = Vec::with_capacity(page_keys.len());
+    for (account_info, option_key) in ctx.remaining_accounts.iter().zip(page_keys) {
+        require_keys_eq!(account_info.key(), *option_key);
+        let option_detail = Account::<OptionDetail>::try_from(account_info)?;
+        options.push(OpenOptionSummary {
+            option: *option_key,
+            owner: option_detail.owner,
+            custody: option_detail.custody,
+            strike_price: option_detail.strike_price,
+            strike_exponent: option_detail.strike_exponent,
+            amount: option_detail.amount,
+            expired_date: option_detail.expired_date,
+            is_call: option_detail.option_type == 0,
+        });
+    }
+
+    Ok(OpenOptionsPage {
+        options,
+        total: pool.open_options,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetOpenOptionsParams)]
+pub struct GetOpenOptions<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        constraint = option_book_page.pool == pool.key() @ PoolError::InvalidPoolState,
+        constraint = option_book_page.index == OptionBookPage::get_book_page(params.page)
+            @ PoolError::InvalidPoolState
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    errors::OptionError,
+    math,
+    state::{Contract, Custody, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetOptionValueParams {
+    pub user: Pubkey,
+    pub option_index: u64,
+    pub pool_name: String,
+}
+
+// Payout in locked tokens an exercise of the whole option would make now, 0 when it can't
+// be exercised or is out of the money. Priced like exercise_option, early exercise penalty included.
+// remaining accounts: backup oracles of the custody, as for exercise_option
+pub fn get_option_value<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetOptionValue<'info>>,
+    _params: &GetOptionValueParams,
+) -> Result<u64> {
+    let option_detail = &ctx.accounts.option_detail;
+    let contract = &ctx.accounts.contract;
+    let custody = &ctx.accounts.custody;
+    let locked_custody = &ctx.accounts.locked_custody;
+
+    let curtime = contract.get_time()?;
+    if !option_detail.valid
+        || option_detail.exercised != 0
+        || !option_detail.is_american
+        || option_detail.expired_date <= curtime
+    {
+        return Ok(0);
+    }
+
+    let token_price = contract.get_oracle_price(&ctx.accounts.locked_oracle, curtime)?;
+    let custody_price = custody.get_oracle_price(
+        contract,
+        curtime,
+        &ctx.accounts.custody_oracle,
+        ctx.remaining_accounts,
+    )?;
+
+    let profit = option_detail.get_exercise_profit(
+        &custody_price,
+        &token_price,
+        custody.decimals,
+        locked_custody.decimals,
+    )?;
+    let remaining_sec = math::checked_sub(option_detail.expired_date, curtime)?;
+    let tenor_sec =
+        math::checked_sub(option_detail.expired_date, option_detail.purchase_date as i64)?;
+
+    math::checked_sub(
+        profit,
+        locked_custody.get_early_exercise_penalty(profit, remaining_sec, tenor_sec)?,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetOptionValueParams)]
+pub struct GetOptionValue<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody_mint.key().as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>, // Target price asset
+
+    #[account(
+        seeds = [b"option", params.user.as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump,
+        constraint = option_detail.locked_asset == locked_custody.key() @ OptionError::InvalidMintError
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 locked_custody_mint.key().as_ref()],
+        bump = locked_custody.bump
+    )]
+    pub locked_custody: Box<Account<'info, Custody>>,
+
+    /// CHECK: oracle account for the locked token
+    #[account(
+        constraint = locked_oracle.key() == locked_custody.oracle
+    )]
+    pub locked_oracle: AccountInfo<'info>,
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle.key() == custody.oracle
+    )]
+    pub custody_oracle: AccountInfo<'info>,
+}
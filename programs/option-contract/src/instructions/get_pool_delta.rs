@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::PoolError,
+    math,
+    state::{Contract, Custody, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetPoolDeltaParams {
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct PoolDelta {
+    pub net_delta: i64, // delta of the pool as option writer, in custody tokens
+}
+
+pub fn get_pool_delta<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetPoolDelta<'info>>,
+    _params: &GetPoolDeltaParams,
+) -> Result<PoolDelta> {
+    let pool = &ctx.accounts.pool;
+    let custody = &ctx.accounts.custody;
+    let curtime = ctx.accounts.contract.get_time()?;
+    let token_price =
+        ctx.accounts.contract.get_oracle_price(&ctx.accounts.custody_oracle_account, curtime)?;
+    let oracle_price = token_price.get_price();
+
+    // holders are long the options, the pool as writer holds the opposite delta
+    let mut holder_delta = 0.0;
+    for account_info in ctx.remaining_accounts.iter() {
+        let option_detail = Account::<OptionDetail>::try_from(account_info)?;
+        require_keys_eq!(option_detail.pool, pool.key(), PoolError::InvalidPoolState);
+        require_keys_eq!(option_detail.custody, custody.key(), PoolError::InvalidCustodyState);
+
+        // only open options carry delta
+        if !option_detail.valid || option_detail.exercised != 0 {
+            continue;
+        }
+
+        holder_delta += option_detail.get_delta(oracle_price, curtime, custody.volatility_bps)?
+            * math::checked_as_f64(option_detail.quantity)?;
+    }
+
+    let net_delta = -holder_delta * math::checked_powi(10.0, custody.decimals as i32)?;
+    if !net_delta.is_finite() || net_delta.abs() > i64::MAX as f64 {
+        return err!(crate::errors::MathError::OverflowMathError);
+    }
+
+    Ok(PoolDelta {
+        net_delta: net_delta as i64,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetPoolDeltaParams)]
+pub struct GetPoolDelta<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>, // Target price asset
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle_account.key() == custody.oracle
+    )]
+    pub custody_oracle_account: AccountInfo<'info>,
+    // remaining accounts:
+    //   open option detail accounts of the pool on this custody (read-only, unsigned)
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Contract, Pool};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetRealizedPnlParams {
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct RealizedPnl {
+    pub cumulative_premium_usd: u128,
+    pub cumulative_payout_usd: u128,
+    pub realized_pnl_usd: i128,
+}
+
+pub fn get_realized_pnl(
+    ctx: Context<GetRealizedPnl>,
+    _params: &GetRealizedPnlParams,
+) -> Result<RealizedPnl> {
+    let pool = &ctx.accounts.pool;
+
+    Ok(RealizedPnl {
+        cumulative_premium_usd: pool.cumulative_premium_usd,
+        cumulative_payout_usd: pool.cumulative_payout_usd,
+        realized_pnl_usd: pool.get_realized_pnl_usd()?,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetRealizedPnlParams)]
+pub struct GetRealizedPnl<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Contract, Custody, Pool, SettlementSnapshot};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetSettlementPricesParams {
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct SettlementPrices {
+    pub settlements: Vec<SettlementSnapshot>, // oldest first
+}
+
+pub fn get_settlement_prices(
+    ctx: Context<GetSettlementPrices>,
+    _params: &GetSettlementPricesParams,
+) -> Result<SettlementPrices> {
+    Ok(SettlementPrices {
+        settlements: ctx.accounts.custody.get_settlement_prices(),
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetSettlementPricesParams)]
+pub struct GetSettlementPrices<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::{OptionError, PoolError},
+    math,
+    state::{Contract, Custody, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct GetUserPortfolioValueParams {
+    pub user: Pubkey,
+    pub pool_name: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct PortfolioValue {
+    pub value_usd: u64,   // with USD_DECIMALS
+    pub value_token: u64, // in custody tokens
+}
+
+pub fn get_user_portfolio_value<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetUserPortfolioValue<'info>>,
+    params: &GetUserPortfolioValueParams,
+) -> Result<PortfolioValue> {
+    let custody = &ctx.accounts.custody;
+    let curtime = ctx.accounts.contract.get_time()?;
+    let token_price =
+        ctx.accounts.contract.get_oracle_price(&ctx.accounts.custody_oracle_account, curtime)?;
+    let oracle_price = token_price.get_price();
+
+    let mut value_usd = 0.0;
+    for account_info in ctx.remaining_accounts.iter() {
+        let option_detail = Account::<OptionDetail>::try_from(account_info)?;
+        require_keys_eq!(option_detail.owner, params.user, OptionError::InvalidOwner);
+        require_keys_eq!(option_detail.custody, custody.key(), PoolError::InvalidCustodyState);
+
+        // exercised, bought back or expired options are not part of the portfolio
+        if !option_detail.valid || option_detail.exercised != 0 {
+            continue;
+        }
+
+        value_usd += option_detail.get_fair_value_usd(oracle_price, curtime, custody.volatility_bps)?;
+    }
+
+    let value_usd = math::checked_as_u64(math::checked_float_mul(
+        value_usd,
+        math::checked_powi(10.0, Contract::USD_DECIMALS as i32)?,
+    )?)?;
+
+    Ok(PortfolioValue {
+        value_usd,
+        value_token: token_price.get_token_amount(value_usd, custody.decimals)?,
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(params: GetUserPortfolioValueParams)]
+pub struct GetUserPortfolioValue<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>, // Target price asset
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle_account.key() == custody.oracle
+    )]
+    pub custody_oracle_account: AccountInfo<'info>,
+    // remaining accounts:
+    //   option detail accounts of the user on this custody (read-only, unsigned)
+}
@@ -1,18 +1,42 @@
 use anchor_lang::prelude::*;
-use crate::state::{Contract, Multisig};
+use crate::state::{Contract, Multisig, OraclePrice};
 use anchor_spl::token::Token;
 
-// Create Lp PDA Account and init, store bump.
-pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitializeParams {
+  pub min_signatures: u8,
+  pub max_price_age_sec: u64, // oracle staleness limit
+  pub max_price_conf_bps: u64, // oracle confidence limit, 0 = unchecked
+  pub fee_bps: u16, // protocol share of option premiums
+  pub fee_recipient: Pubkey, // owner of the token accounts protocol fees are paid to
+}
+
+// Create Contract, transfer authority and Multisig PDAs in one go and store their bumps,
+// everything add_pool / add_custody and the option instructions rely on.
+// remaining accounts: 1 to Multisig::MAX_SIGNERS admin signers (read-only, unsigned)
+pub fn initialize(ctx: Context<Initialize>, params: &InitializeParams) -> Result<()> {
   let contract = &mut ctx.accounts.contract;
 
+  // validate inputs
+  if params.max_price_age_sec == 0
+    || params.max_price_conf_bps > Contract::BPS_POWER as u64
+    || params.fee_bps as u128 > Contract::BPS_POWER
+  {
+    return Err(ProgramError::InvalidArgument.into());
+  }
+
   // initialize multisig, this will fail if account is already initialized
   let mut multisig = ctx.accounts.multisig.load_init()?;
-  multisig.set_signers(ctx.remaining_accounts, 1)?;
+  multisig.set_signers(ctx.remaining_accounts, params.min_signatures)?;
 
   // store PDA bumps
   contract.bump = ctx.bumps.contract;
   contract.transfer_authority_bump = ctx.bumps.transfer_authority;
+  contract.max_price_age_sec = params.max_price_age_sec;
+  contract.max_price_conf_bps = params.max_price_conf_bps;
+  contract.fee_bps = params.fee_bps;
+  contract.fee_recipient = params.fee_recipient;
+  contract.max_publish_time_skew_sec = OraclePrice::DEFAULT_PUBLISH_TIME_SKEW_SEC;
   multisig.bump = ctx.bumps.multisig;
   Ok(())
 }
@@ -32,7 +56,7 @@ pub struct Initialize<'info> {
   )]
   pub multisig: AccountLoader<'info, Multisig>,
 
-  // LP PDA account stored Lp status including wsol, usdc account and locked amounts
+  // Contract PDA with the pool list and PDA bumps
   #[account(
     init, 
     payer = signer,  
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::OptionError,
+    math,
+    state::{Contract, Custody, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MarkOptionParams {
+    pub user: Pubkey,
+    pub option_index: u64,
+    pub pool_name: String,
+}
+
+pub fn mark_option(ctx: Context<MarkOption>, _params: &MarkOptionParams) -> Result<()> {
+    let option_detail = &mut ctx.accounts.option_detail;
+    let curtime = ctx.accounts.contract.get_time()?;
+
+    // exercised, bought back or expired options have no mark to keep up to date
+    require!(
+        option_detail.valid && option_detail.exercised == 0,
+        OptionError::OptionNotValid
+    );
+
+    let token_price =
+        ctx.accounts.contract.get_oracle_price(&ctx.accounts.custody_oracle_account, curtime)?;
+    let value_usd = option_detail.get_fair_value_usd(
+        token_price.get_price(),
+        curtime,
+        ctx.accounts.custody.volatility_bps,
+    )?;
+
+    option_detail.last_mark = math::checked_as_u64(math::checked_float_mul(
+        value_usd,
+        math::checked_powi(10.0, Contract::USD_DECIMALS as i32)?,
+    )?)?;
+    option_detail.last_mark_time = curtime;
+    msg!("Option marked at {} usd", value_usd);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: MarkOptionParams)]
+pub struct MarkOption<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle_account.key() == custody.oracle
+    )]
+    pub custody_oracle_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"option", params.user.as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+}
@@ -15,6 +15,64 @@ pub use create_lp_mint::*;
 pub use add_pool::*;
 pub use claim_option::*;
 pub use realloc_pool::*;
+pub use set_premium_multiplier::*;
+pub use roll_expired::*;
+pub use get_locked_collateral::*;
+pub use set_premium_cap::*;
+pub use rebalance_ack::*;
+pub use get_exercisable_profit::*;
+pub use get_realized_pnl::*;
+pub use set_collateral_buffer::*;
+pub use set_exercise_delegate::*;
+pub use set_write_cooldown::*;
+pub use monitor_price_move::*;
+pub use get_user_portfolio_value::*;
+pub use freeze_option::*;
+pub use unfreeze_option::*;
+pub use set_lot_size::*;
+pub use set_allowed_premium_mints::*;
+pub use set_claim_window::*;
+pub use get_settlement_prices::*;
+pub use set_min_premium_ratio::*;
+pub use add_fee_exempt::*;
+pub use remove_fee_exempt::*;
+pub use set_backup_oracles::*;
+pub use set_settlement_cap::*;
+pub use mark_option::*;
+pub use set_early_exercise_penalty::*;
+pub use set_quote_custody::*;
+pub use get_pool_delta::*;
+pub use set_rent_buffer::*;
+pub use set_exercise_trigger::*;
+pub use set_max_decimals_diff::*;
+pub use get_implied_volatility::*;
+pub use repair_user_index::*;
+pub use set_volatility::*;
+pub use record_settlement_read::*;
+pub use set_settlement_window::*;
+pub use add_keeper::*;
+pub use remove_keeper::*;
+pub use set_risk_group::*;
+pub use set_protocol_fee::*;
+pub use close_options_batch::*;
+pub use get_open_options::*;
+pub use set_deposit_cap::*;
+pub use set_min_exercise_gap::*;
+pub use set_auto_exercise_reserve::*;
+pub use set_contract_size::*;
+pub use set_paused::*;
+pub use set_max_open_options::*;
+pub use set_max_utilization::*;
+pub use set_min_premium::*;
+pub use update_contract_params::*;
+pub use set_pricing_model::*;
+pub use get_option_value::*;
+pub use get_deposit_capacity::*;
+pub use auto_exercise_batch::*;
+pub use close_option_detail::*;
+pub use transfer_option::*;
+pub use set_maker_rebate::*;
+pub use withdraw_reserve::*;
 
 pub mod close_option;
 pub mod exercise_option;
@@ -32,4 +90,62 @@ pub mod add_liquidity;
 pub mod remove_liquidity;
 pub mod create_lp_mint;
 pub mod claim_option;
-pub mod realloc_pool;
\ No newline at end of file
+pub mod realloc_pool;
+pub mod set_premium_multiplier;
+pub mod roll_expired;
+pub mod get_locked_collateral;
+pub mod set_premium_cap;
+pub mod rebalance_ack;
+pub mod get_exercisable_profit;
+pub mod get_realized_pnl;
+pub mod set_collateral_buffer;
+pub mod set_exercise_delegate;
+pub mod set_write_cooldown;
+pub mod monitor_price_move;
+pub mod get_user_portfolio_value;
+pub mod freeze_option;
+pub mod unfreeze_option;
+pub mod set_lot_size;
+pub mod set_allowed_premium_mints;
+pub mod set_claim_window;
+pub mod get_settlement_prices;
+pub mod set_min_premium_ratio;
+pub mod add_fee_exempt;
+pub mod remove_fee_exempt;
+pub mod set_backup_oracles;
+pub mod set_settlement_cap;
+pub mod mark_option;
+pub mod set_early_exercise_penalty;
+pub mod set_quote_custody;
+pub mod get_pool_delta;
+pub mod set_rent_buffer;
+pub mod set_exercise_trigger;
+pub mod set_max_decimals_diff;
+pub mod get_implied_volatility;
+pub mod repair_user_index;
+pub mod set_volatility;
+pub mod record_settlement_read;
+pub mod set_settlement_window;
+pub mod add_keeper;
+pub mod remove_keeper;
+pub mod set_risk_group;
+pub mod set_protocol_fee;
+pub mod close_options_batch;
+pub mod get_open_options;
+pub mod set_deposit_cap;
+pub mod set_min_exercise_gap;
+pub mod set_auto_exercise_reserve;
+pub mod set_contract_size;
+pub mod set_paused;
+pub mod set_max_open_options;
+pub mod set_max_utilization;
+pub mod set_min_premium;
+pub mod update_contract_params;
+pub mod set_pricing_model;
+pub mod get_option_value;
+pub mod get_deposit_capacity;
+pub mod auto_exercise_batch;
+pub mod close_option_detail;
+pub mod transfer_option;
+pub mod set_maker_rebate;
+pub mod withdraw_reserve;
\ No newline at end of file
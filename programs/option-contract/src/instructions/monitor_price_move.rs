@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Contract, Custody, Pool};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MonitorPriceMoveParams {
+    pub pool_name: String,
+}
+
+// Permissionless keeper crank: records sharp oracle moves so the write cooldown
+// is persisted even when no write attempt succeeds in between
+pub fn monitor_price_move(
+    ctx: Context<MonitorPriceMove>,
+    _params: &MonitorPriceMoveParams,
+) -> Result<()> {
+    let curtime = ctx.accounts.contract.get_time()?;
+    let token_price =
+        ctx.accounts.contract.get_oracle_price(&ctx.accounts.custody_oracle_account, curtime)?;
+
+    let custody = ctx.accounts.custody.as_mut();
+    custody.update_price_move(&token_price, curtime)?;
+    msg!("Writes paused until: {}", custody.write_paused_until);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: MonitorPriceMoveParams)]
+pub struct MonitorPriceMove<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+
+    /// CHECK: oracle account for the custody token
+    #[account(
+        constraint = custody_oracle_account.key() == custody.oracle
+    )]
+    pub custody_oracle_account: AccountInfo<'info>,
+}
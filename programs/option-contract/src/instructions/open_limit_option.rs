@@ -1,29 +1,37 @@
 use crate::{
-    errors::OptionError,
+    errors::{ContractError, OptionError},
+    instructions::OptionSold,
     math,
-    state::{Contract, Custody, OptionDetail, OraclePrice, Pool, User},
+    state::{Contract, Custody, OptionBookPage, OptionDetail, OraclePrice, Pool, User},
 };
 use anchor_lang::prelude::*;
-use anchor_spl::
-    token::{self, Mint, Token, TokenAccount, Transfer as SplTransfer};
+use anchor_spl::token_interface::{
+    self, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct OpenLimitOptionParams {
-    amount: u64, // WSOL/USDC account for options, call option - SOL amount, Put option - USDC amount
-    strike: f64, // Strike price
+    amount: u64, // WSOL/quote account for options, call option - SOL amount, Put option - quote stablecoin amount
+    strike: u64, // Strike price, scaled by the custody strike exponent
     period: u64, // Number of days from option creation to expiration
     expired_time: u64, // when the option is expired : Unix epoch time
     pool_name : String,
-    limit_price: f64
+    limit_price: f64,
+    book_page: u32, // page of the pool option book to list the option on, one with room or the next new page
 }
 
-pub fn open_limit_option(ctx: Context<OpenLimitOption>, params: &OpenLimitOptionParams) -> Result<()> {
+// remaining accounts: when the locked custody has a risk group cap, the custodies of the pool
+// followed by their oracles, as for the AUM
+pub fn open_limit_option<'info>(
+    ctx: Context<'_, '_, 'info, 'info, OpenLimitOption<'info>>,
+    params: &OpenLimitOptionParams,
+) -> Result<()> {
     let owner = &ctx.accounts.owner;
     let token_program = &ctx.accounts.token_program;
     let option_detail = &mut ctx.accounts.option_detail;
     let contract = &ctx.accounts.contract;
     let user = &mut ctx.accounts.user;
-    let pool = &ctx.accounts.pool;
+    let pool = &mut ctx.accounts.pool;
     let custody = &mut ctx.accounts.custody;
     let custody_oracle_account = &ctx.accounts.custody_oracle_account;
     let locked_custody = &mut ctx.accounts.locked_custody;
@@ -34,10 +42,38 @@ pub fn open_limit_option(ctx: Context<OpenLimitOption>, params: &OpenLimitOption
 
     let funding_account = &ctx.accounts.funding_account;
 
-    let option_index = user.option_index + 1;
+    // Sales halt in an emergency, exercises and withdrawals stay open for users to exit
+    require!(!contract.paused, ContractError::ContractPaused);
+
+    let option_index = user.next_option_index()?;
     // compute position price
     let curtime = contract.get_time()?;
 
+    // The premium is priced on period, which has to match the expiry
+    OptionDetail::validate_expiry(params.period, params.expired_time, curtime)?;
+
+    // Writes are paused once the premium cap is hit until admins acknowledge a rebalance
+    require!(
+        !pay_custody.is_premium_cap_reached()?,
+        OptionError::PremiumCapReachedError
+    );
+
+    // Premiums can only be paid in the currencies the pool accepts
+    require!(
+        pool.is_premium_mint_allowed(&pay_custody.mint),
+        OptionError::PremiumMintNotAllowed
+    );
+
+    // Settlement math between the tokens stays within safe precision
+    pool.check_decimals_diff(pay_custody.decimals, locked_custody.decimals)?;
+    pool.check_decimals_diff(custody.decimals, locked_custody.decimals)?;
+
+    // Puts are secured by the quote custody of the pool
+    require!(
+        custody.key() == locked_custody.key() || pool.is_put_collateral(&locked_custody.key()),
+        OptionError::InvalidQuoteCustodyError
+    );
+
     // Check if the user's token balance is enough to pay premium
     require_gte!(
         funding_account.amount,
@@ -46,42 +82,56 @@ pub fn open_limit_option(ctx: Context<OpenLimitOption>, params: &OpenLimitOption
     );
 
     // Send Pay token from User to Pool Custody as premium
-    // token::transfer(
+    // token_interface::transfer_checked(
     //     CpiContext::new(
     //         token_program.to_account_info(),
-    //         SplTransfer {
+    //         TransferChecked {
     //             from: funding_account.to_account_info(),
+    //             mint: pay_custody_mint.to_account_info(),
     //             to: pay_custody_token_account.to_account_info(),
     //             authority: owner.to_account_info(),
     //         },
     //     ),
     //     params.amount,
+    //     pay_custody_mint.decimals,
     // )?;
     
-    let token_price = OraclePrice::new_from_oracle(custody_oracle_account, curtime, false)?;
+    let token_price = contract.get_oracle_price(custody_oracle_account, curtime)?;
+
+    // Writes pause for a cooldown after a sharp move of the custody oracle, tracked on the
+    // copy written back last when custody is also the pay or locked custody
+    let price_move_custody =
+        Custody::last_written(Custody::last_written(custody, pay_custody), locked_custody);
+    price_move_custody.update_price_move(&token_price, curtime)?;
+    require!(
+        !price_move_custody.is_write_paused(curtime),
+        OptionError::WriteCooldownError
+    );
 
     let oracle_price = token_price.get_price();
     let period_year = math::checked_as_f64(math::checked_float_div(params.period as f64, 365.0)?)?;
 
     msg!("oracle_price: {}", oracle_price);
+    let strike_price = OraclePrice::new(params.strike, custody.strike_exponent);
     msg!("params.strike: {}", params.strike);
     msg!("period_year: {}", period_year);
     // Calculate Premium in usd using black scholes formula.
-    let premium = OptionDetail::black_scholes(
+    let premium = OptionDetail::get_option_price(
+        custody.pricing_model,
         oracle_price,
-        params.strike,
+        strike_price.checked_as_f64()?,
         period_year,
         custody.key() == locked_custody.key(),
-    );
+        custody.volatility_bps,
+    )?;
+    let premium = custody.apply_premium_multiplier(premium)?;
     msg!("premium: {}", premium);
 
-    let pay_token_price = OraclePrice::new_from_oracle(pay_custody_oracle_account, curtime, false)?;
+    let pay_token_price = contract.get_oracle_price(pay_custody_oracle_account, curtime)?;
 
     // Calculate Premium in pay_toke amount
-    let pay_amount = math::checked_as_u64(
-        math::checked_float_div(premium, pay_token_price.get_price())?
-            * math::checked_powi(10.0, pay_custody.decimals as i32)?,
-    )?;
+    let premium_usd = OptionDetail::get_premium_usd(premium)?;
+    let pay_amount = pay_token_price.get_token_amount(premium_usd, pay_custody.decimals)?;
 
     require_gt!(
         pay_amount,
@@ -89,18 +139,37 @@ pub fn open_limit_option(ctx: Context<OpenLimitOption>, params: &OpenLimitOption
         OptionError::InvalidPayAmountError
     );
 
-    // Add premium to liquidity pool
-    pay_custody.token_owned = math::checked_add(pay_custody.token_owned, params.amount)?;
+    // Dust premiums would hand out optionality for free
+    require_gte!(
+        pay_token_price.get_asset_amount_usd(params.amount, pay_custody.decimals)?,
+        contract.min_premium_usd,
+        OptionError::PremiumTooSmallError
+    );
+
+    // Add premium to liquidity pool, on the copy written back last when pay_custody is also
+    // the locked custody
+    Custody::last_written(pay_custody, locked_custody).collect_premium(params.amount)?;
+    pool.record_premium(pay_token_price.get_asset_amount_usd(params.amount, pay_custody.decimals)?)?;
     option_detail.premium = pay_amount;
     option_detail.premium_asset = pay_custody.key();
 
     let quantity = math::checked_div(params.amount, pay_amount)?;
     msg!("quantity: {}", quantity);
 
-    let decimals_multiplier = math::checked_powi(10.0, pay_custody.decimals as i32)?;
+    // Lock the max loss plus the collateral buffer of the locked custody, in whole lots
+    let locked_amount = locked_custody.apply_lot_size(locked_custody.apply_collateral_buffer(
+        OptionDetail::get_max_loss(
+            quantity,
+            &strike_price,
+            custody.key() == locked_custody.key(),
+            None, // limit options are uncapped
+            locked_custody.decimals,
+        )?
+    )?)?;
+    pool.check_utilization(locked_custody, locked_amount)?;
     locked_custody.token_locked = math::checked_add(
         locked_custody.token_locked,
-        math::checked_as_u64(quantity as f64 * decimals_multiplier)?
+        locked_amount
     )?;
 
     require_gte!(
@@ -109,23 +178,74 @@ pub fn open_limit_option(ctx: Context<OpenLimitOption>, params: &OpenLimitOption
         OptionError::InvalidPoolBalanceError
     );
 
+    // Correlated custodies share the locked value cap of their risk group
+    let risk_group_cap = pool.get_risk_group_cap(locked_custody.risk_group);
+    if risk_group_cap > 0 {
+        let risk_group_locked_usd = pool.get_risk_group_locked_usd(
+            contract,
+            &locked_custody.key(),
+            locked_custody,
+            ctx.remaining_accounts,
+            curtime,
+        )?;
+        require_gte!(
+            risk_group_cap as u128,
+            risk_group_locked_usd,
+            OptionError::RiskGroupCapReachedError
+        );
+    }
+
+    // Every option carries a non zero contract size backed by non zero collateral
+    require!(
+        quantity > 0 && locked_amount > 0,
+        OptionError::InvalidQuantityError
+    );
+
     // store option data
-    option_detail.amount = params.amount;
+    option_detail.amount = locked_amount;
     option_detail.quantity = quantity;
     option_detail.owner = owner.key();
     option_detail.index = option_index;
     option_detail.period = params.period;
     option_detail.expired_date = params.expired_time as i64;
     option_detail.purchase_date = curtime as u64;
+    option_detail.created_slot = contract.get_slot()?;
     option_detail.option_type = if custody.key() == locked_custody.key() { 0 } else { 1 };
     option_detail.strike_price = params.strike;
+    option_detail.strike_exponent = custody.strike_exponent;
+    option_detail.breakeven_price = option_detail.get_breakeven_price(&OraclePrice::new(
+        pay_token_price.get_asset_amount_usd(pay_amount, pay_custody.decimals)?,
+        -(Contract::USD_DECIMALS as i32),
+    ))?;
     option_detail.valid = true;
+    option_detail.is_american = true;
     option_detail.locked_asset = locked_custody.key();
     option_detail.pool = pool.key();
     option_detail.custody = custody.key();
     option_detail.limit_price = (params.limit_price * 100.0) as u64;
     option_detail.executed = false;
     user.option_index = option_index;
+    user.add_open_option(contract.max_open_options)?;
+    let pool_key = pool.key();
+    option_detail.book_page = params.book_page;
+    pool.add_open_option(
+        pool_key,
+        &mut ctx.accounts.option_book_page,
+        params.book_page,
+        ctx.bumps.option_book_page,
+        option_detail.key(),
+    )?;
+
+    emit!(OptionSold {
+        option_index: option_detail.index,
+        owner: option_detail.owner,
+        strike_price: option_detail.strike_price,
+        strike_exponent: option_detail.strike_exponent,
+        premium: option_detail.premium,
+        amount: option_detail.amount,
+        is_call: option_detail.option_type == 0,
+        expired_date: option_detail.expired_date,
+    });
 
     Ok(())
 }
@@ -137,7 +257,7 @@ pub struct OpenLimitOption<'info> {
     pub owner: Signer<'info>,
 
     #[account(mut)]
-    pub funding_account: Box<Account<'info, TokenAccount>>,
+    pub funding_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: empty PDA, authority for token accounts
     #[account(
@@ -189,12 +309,24 @@ pub struct OpenLimitOption<'info> {
       payer = owner,
       space=OptionDetail::LEN,
       seeds = [b"option", owner.key().as_ref(), 
-            (user.option_index+1).to_le_bytes().as_ref(),
+            // wraps only at u64::MAX, where next_option_index() rejects the instruction
+            user.option_index.wrapping_add(1).to_le_bytes().as_ref(),
             pool.key().as_ref(), custody.key().as_ref()],
         bump
     )]
     pub option_detail: Box<Account<'info, OptionDetail>>,
 
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OptionBookPage::LEN,
+        seeds = [b"option_book",
+                 pool.key().as_ref(),
+                 params.book_page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+
     #[account(
         mut,
         seeds = [b"custody",
@@ -211,7 +343,7 @@ pub struct OpenLimitOption<'info> {
                  pay_custody.mint.key().as_ref()],
         bump
     )]
-    pub pay_custody_token_account: Box<Account<'info, TokenAccount>>,
+    pub pay_custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: oracle account for the position token
     #[account(
@@ -228,11 +360,11 @@ pub struct OpenLimitOption<'info> {
     )]
     pub locked_custody: Box<Account<'info, Custody>>, // locked asset
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
-    pub pay_custody_mint: Box<Account<'info, Mint>>,
+    pub pay_custody_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
-    pub locked_custody_mint: Box<Account<'info, Mint>>,
-    pub token_program: Program<'info, Token>,
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
@@ -1,86 +1,460 @@
 use crate::{
-    errors::OptionError,
+    errors::{ContractError, OptionError},
     math,
-    state::{Contract, Custody, OptionDetail, OraclePrice, Pool, User},
+    state::{
+        Contract, Custody, OptionBookPage, OptionDetail, OraclePrice, Pool, SettlementKind, User,
+    },
 };
 use anchor_lang::prelude::*;
-use anchor_spl::
-    token::{self, Mint, Token, TokenAccount, Transfer as SplTransfer};
+use anchor_spl::token_interface::{
+    self, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct OpenOptionParams {
-    amount: u64, // WSOL/USDC account for options, call option - SOL amount, Put option - USDC amount
-    strike: f64, // Strike price
+    amount: u64, // premium budget in the pay token the quantity is sized by, unused when contracts > 0
+    contracts: u64, // options to write in contracts of the custody contract_size, 0 - sized by amount
+    strike: u64, // Strike price, scaled by the custody strike exponent
     period: u64, // Number of days from option creation to expiration
     expired_time: u64, // when the option is expired : Unix epoch time
     pool_name : String,
+    premium_split_bps: u64, // part of the premium paid with the secondary pay asset, 0 - all in pay asset
+    profit_cap: Option<u64>, // max profit per unit in usd (USD_DECIMALS), None - uncapped
+    allow_partial: bool, // fill up to the liquidity of the pool instead of failing
+    is_american: bool, // exercisable before expiry, false - european, settled at expiry only
+    settlement: SettlementKind, // of exercises, physical settlement is for uncapped calls only
+    max_premium: u64, // max premium per option in the pay token, 0 = any
+    min_premium: u64, // min premium per option in the pay token, 0 = any
+    book_page: u32, // page of the pool option book to list the option on, one with room or the next new page
+}
+
+#[event]
+pub struct OptionSold {
+    pub option_index: u64,
+    pub owner: Pubkey,
+    pub strike_price: u64, // scaled by strike_exponent
+    pub strike_exponent: i32,
+    pub premium: u64,
+    pub amount: u64,
+    pub is_call: bool,
+    pub expired_date: i64,
 }
 
-pub fn open_option(ctx: Context<OpenOption>, params: &OpenOptionParams) -> Result<()> {
+// remaining accounts: when the locked custody has a risk group cap, the custodies of the pool
+// followed by their oracles, as for the AUM
+pub fn open_option<'info>(
+    ctx: Context<'_, '_, 'info, 'info, OpenOption<'info>>,
+    params: &OpenOptionParams,
+) -> Result<u64> {
     let owner = &ctx.accounts.owner;
     let token_program = &ctx.accounts.token_program;
     let option_detail = &mut ctx.accounts.option_detail;
-    let contract = &ctx.accounts.contract;
+    let contract = &mut ctx.accounts.contract;
     let user = &mut ctx.accounts.user;
-    let pool = &ctx.accounts.pool;
+    let pool = &mut ctx.accounts.pool;
     let custody = &mut ctx.accounts.custody;
     let custody_oracle_account = &ctx.accounts.custody_oracle_account;
     let locked_custody = &mut ctx.accounts.locked_custody;
+    let locked_custody_oracle_account = &ctx.accounts.locked_custody_oracle_account;
 
     let pay_custody = &mut ctx.accounts.pay_custody;
     let pay_custody_oracle_account = &ctx.accounts.pay_custody_oracle_account;
     let pay_custody_token_account = &ctx.accounts.pay_custody_token_account;
+    let pay_custody_mint = &ctx.accounts.pay_custody_mint;
 
     let funding_account = &ctx.accounts.funding_account;
 
-    let option_index = user.option_index + 1;
+    // Sales halt in an emergency, exercises and withdrawals stay open for users to exit
+    require!(!contract.paused, ContractError::ContractPaused);
+
+    let option_index = user.next_option_index()?;
     // compute position price
     let curtime = contract.get_time()?;
 
+    // The premium is priced on period, which has to match the expiry
+    OptionDetail::validate_expiry(params.period, params.expired_time, curtime)?;
+
+    // Writes are paused once the premium cap is hit until admins acknowledge a rebalance
+    require!(
+        !pay_custody.is_premium_cap_reached()?,
+        OptionError::PremiumCapReachedError
+    );
+
+    // Premiums can only be paid in the currencies the pool accepts
+    require!(
+        pool.is_premium_mint_allowed(&pay_custody.mint),
+        OptionError::PremiumMintNotAllowed
+    );
+
+    // Settlement math between the tokens stays within safe precision
+    pool.check_decimals_diff(pay_custody.decimals, locked_custody.decimals)?;
+    pool.check_decimals_diff(custody.decimals, locked_custody.decimals)?;
+
+    // Puts are secured by the quote custody of the pool
+    require!(
+        custody.key() == locked_custody.key() || pool.is_put_collateral(&locked_custody.key()),
+        OptionError::InvalidQuoteCustodyError
+    );
+
+    // Physical delivery needs the whole underlying locked, as uncapped calls do
+    require!(
+        params.settlement == SettlementKind::Cash
+            || (custody.key() == locked_custody.key() && params.profit_cap.is_none()),
+        OptionError::InvalidSettlementError
+    );
+
+    // Part of the premium value can be paid with a secondary asset instead of the pay asset
+    require_gte!(
+        Contract::BPS_POWER as u64,
+        params.premium_split_bps,
+        OptionError::InvalidPremiumSplitError
+    );
+    // Each custody is priced by the oracle program it was set up with
+    custody.check_oracle_kind(custody_oracle_account)?;
+    pay_custody.check_oracle_kind(pay_custody_oracle_account)?;
+    locked_custody.check_oracle_kind(locked_custody_oracle_account)?;
+    let token_price = contract.get_oracle_price(custody_oracle_account, curtime)?;
+
+    // Writes pause for a cooldown after a sharp move of the custody oracle, tracked on the
+    // copy written back last when custody is also the pay or locked custody
+    let price_move_custody =
+        Custody::last_written(Custody::last_written(custody, pay_custody), locked_custody);
+    price_move_custody.update_price_move(&token_price, curtime)?;
+    require!(
+        !price_move_custody.is_write_paused(curtime),
+        OptionError::WriteCooldownError
+    );
+
+    let strike_price = OraclePrice::new(params.strike, custody.strike_exponent);
+    let is_call = custody.key() == locked_custody.key();
+    let pay_token_price = contract.get_oracle_price(pay_custody_oracle_account, curtime)?;
+    let pay_amount = get_write_premium(
+        custody,
+        &token_price,
+        &strike_price,
+        params.period,
+        is_call,
+        params.profit_cap,
+        &pay_token_price,
+        pay_custody.decimals,
+        params.min_premium,
+        params.max_premium,
+    )?;
+
+    // Lock the max loss plus the collateral buffer of the locked custody
+    let requested_quantity = if params.contracts > 0 {
+        custody.get_contracts_quantity(params.contracts)?
+    } else {
+        math::checked_div(params.amount, pay_amount)?
+    };
+    let requested_locked_amount =
+        locked_custody.apply_collateral_buffer(OptionDetail::get_max_loss(
+            requested_quantity,
+            &strike_price,
+            is_call,
+            params.profit_cap,
+            locked_custody.decimals,
+        )?)?;
+
+    // Partial fills write as many whole options as the pool can still back
+    let available_amount =
+        math::checked_sub(locked_custody.token_owned, locked_custody.token_locked)?;
+    let quantity = if params.allow_partial && requested_locked_amount > available_amount {
+        let filled_quantity = math::checked_as_u64(math::checked_div(
+            math::checked_mul(requested_quantity as u128, available_amount as u128)?,
+            requested_locked_amount as u128,
+        )?)?;
+        // in whole contracts when sized by contracts
+        let filled_quantity = if params.contracts > 0 {
+            custody.round_to_contracts(filled_quantity)
+        } else {
+            filled_quantity
+        };
+        require_gt!(filled_quantity, 0, OptionError::InvalidPoolBalanceError);
+        filled_quantity
+    } else {
+        requested_quantity
+    };
+    msg!("quantity: {} of {}", quantity, requested_quantity);
+
+    // Filled options pay the proportional premium
+    let amount = if params.contracts > 0 || quantity < requested_quantity {
+        math::checked_mul(quantity, pay_amount)?
+    } else {
+        params.amount
+    };
+
+    let secondary_pay_value = math::checked_as_u64(math::checked_div(
+        math::checked_mul(amount as u128, params.premium_split_bps as u128)?,
+        Contract::BPS_POWER,
+    )?)?;
+    let primary_pay_amount = math::checked_sub(amount, secondary_pay_value)?;
+
+    // The protocol cut of the whole premium is paid in the pay asset, the rest goes to the pool
+    let protocol_fee = contract.get_protocol_fee(amount)?;
+    // part of the cut is rebated to the pool, which writes the option, within the epoch budget
+    let protocol_fee = math::checked_sub(
+        protocol_fee,
+        contract.take_maker_rebate(
+            protocol_fee,
+            pay_token_price.get_asset_amount_usd(protocol_fee, pay_custody.decimals)?,
+            curtime,
+        )?,
+    )?;
+    require_gte!(
+        primary_pay_amount,
+        protocol_fee,
+        OptionError::InvalidPremiumSplitError
+    );
+    let pool_pay_amount = math::checked_sub(primary_pay_amount, protocol_fee)?;
+
     // Check if the user's token balance is enough to pay premium
     require_gte!(
         funding_account.amount,
-        params.amount,
+        primary_pay_amount,
         OptionError::InvalidSignerBalanceError
     );
 
     // Send Pay token from User to Pool Custody as premium
-    token::transfer(
+    token_interface::transfer_checked(
         CpiContext::new(
             token_program.to_account_info(),
-            SplTransfer {
+            TransferChecked {
                 from: funding_account.to_account_info(),
+                mint: pay_custody_mint.to_account_info(),
                 to: pay_custody_token_account.to_account_info(),
                 authority: owner.to_account_info(),
             },
         ),
-        params.amount,
+        pool_pay_amount,
+        pay_custody_mint.decimals,
+    )?;
+
+    pay_protocol_fee(
+        contract,
+        ctx.accounts.protocol_fee_account.as_deref(),
+        funding_account,
+        pay_custody_mint,
+        owner.to_account_info(),
+        token_program.to_account_info(),
+        protocol_fee,
+        pay_token_price.get_asset_amount_usd(protocol_fee, pay_custody.decimals)?,
+    )?;
+
+    if secondary_pay_value > 0 {
+        let (
+            Some(secondary_funding_account),
+            Some(secondary_pay_custody),
+            Some(secondary_pay_custody_token_account),
+            Some(secondary_pay_custody_oracle_account),
+            Some(secondary_pay_custody_mint),
+        ) = (
+            ctx.accounts.secondary_funding_account.as_ref(),
+            ctx.accounts.secondary_pay_custody.as_mut(),
+            ctx.accounts.secondary_pay_custody_token_account.as_ref(),
+            ctx.accounts.secondary_pay_custody_oracle_account.as_ref(),
+            ctx.accounts.secondary_pay_custody_mint.as_ref(),
+        )
+        else {
+            return err!(OptionError::InvalidPremiumSplitError);
+        };
+        // secondary_pay_custody is written back last, aliasing another custody would drop
+        // the changes made through it
+        require!(
+            secondary_pay_custody.key() != custody.key()
+                && secondary_pay_custody.key() != pay_custody.key()
+                && secondary_pay_custody.key() != locked_custody.key(),
+            OptionError::InvalidPremiumSplitError
+        );
+        require_keys_eq!(
+            secondary_pay_custody_token_account.key(),
+            secondary_pay_custody.token_account
+        );
+        require_keys_eq!(
+            secondary_pay_custody_oracle_account.key(),
+            secondary_pay_custody.oracle
+        );
+        require_keys_eq!(
+            secondary_pay_custody_mint.key(),
+            secondary_pay_custody.mint,
+            OptionError::InvalidMintError
+        );
+        require!(
+            pool.is_premium_mint_allowed(&secondary_pay_custody.mint),
+            OptionError::PremiumMintNotAllowed
+        );
+        pool.check_decimals_diff(secondary_pay_custody.decimals, locked_custody.decimals)?;
+
+        // Value the secondary part in usd and pay the same value in the secondary asset
+        let secondary_token_price =
+            contract.get_oracle_price(secondary_pay_custody_oracle_account, curtime)?;
+        let secondary_pay_amount = secondary_token_price.get_token_amount(
+            pay_token_price.get_asset_amount_usd(secondary_pay_value, pay_custody.decimals)?,
+            secondary_pay_custody.decimals,
+        )?;
+        require_gt!(secondary_pay_amount, 0, OptionError::InvalidPayAmountError);
+        require_gte!(
+            secondary_funding_account.amount,
+            secondary_pay_amount,
+            OptionError::InvalidSignerBalanceError
+        );
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: secondary_funding_account.to_account_info(),
+                    mint: secondary_pay_custody_mint.to_account_info(),
+                    to: secondary_pay_custody_token_account.to_account_info(),
+                    authority: owner.to_account_info(),
+                },
+            ),
+            secondary_pay_amount,
+            secondary_pay_custody_mint.decimals,
+        )?;
+
+        secondary_pay_custody.collect_premium(secondary_pay_amount)?;
+        option_detail.secondary_premium_asset = secondary_pay_custody.key();
+        option_detail.secondary_premium_amount = secondary_pay_amount;
+    }
+    option_detail.premium_split_bps = params.premium_split_bps;
+
+    // Add premium to liquidity pool, on the copy written back last when pay_custody is also
+    // the locked custody
+    Custody::last_written(pay_custody, locked_custody).collect_premium(pool_pay_amount)?;
+    pool.record_premium(pay_token_price.get_asset_amount_usd(
+        math::checked_sub(amount, protocol_fee)?,
+        pay_custody.decimals,
+    )?)?;
+    option_detail.premium = pay_amount;
+    option_detail.premium_asset = pay_custody.key();
+
+    // Lock the max loss plus the collateral buffer of the locked custody, in whole lots
+    let max_loss = OptionDetail::get_max_loss(
+        quantity,
+        &strike_price,
+        is_call,
+        params.profit_cap,
+        locked_custody.decimals,
+    )?;
+    if params.profit_cap.is_some() {
+        option_detail.max_profit = Some(max_loss);
+        option_detail.profit_cap = params.profit_cap;
+    }
+    let locked_amount = locked_custody.apply_lot_size(locked_custody.apply_collateral_buffer(
+        max_loss
+    )?)?;
+    lock_option_collateral(
+        contract,
+        pool,
+        locked_custody,
+        locked_amount,
+        ctx.remaining_accounts,
+        curtime,
+    )?;
+
+    let locked_token_price =
+        contract.get_oracle_price(locked_custody_oracle_account, curtime)?;
+    check_write_premium(
+        contract,
+        locked_custody,
+        pay_token_price.get_asset_amount_usd(amount, pay_custody.decimals)?,
+        locked_token_price.get_asset_amount_usd(locked_amount, locked_custody.decimals)?,
+    )?;
+
+    // Every option carries a non zero contract size backed by non zero collateral
+    require!(
+        quantity > 0 && locked_amount > 0,
+        OptionError::InvalidQuantityError
+    );
+
+    // store option data
+    option_detail.amount = locked_amount;
+    option_detail.quantity = quantity;
+    option_detail.owner = owner.key();
+    option_detail.index = option_index;
+    option_detail.period = params.period;
+    option_detail.expired_date = params.expired_time as i64;
+    option_detail.purchase_date = curtime as u64;
+    option_detail.created_slot = contract.get_slot()?;
+    option_detail.option_type = if custody.key() == locked_custody.key() { 0 } else { 1 };
+    option_detail.strike_price = params.strike;
+    option_detail.strike_exponent = custody.strike_exponent;
+    option_detail.breakeven_price = option_detail.get_breakeven_price(&OraclePrice::new(
+        pay_token_price.get_asset_amount_usd(pay_amount, pay_custody.decimals)?,
+        -(Contract::USD_DECIMALS as i32),
+    ))?;
+    option_detail.valid = true;
+    option_detail.is_american = params.is_american;
+    option_detail.settlement = params.settlement;
+    option_detail.locked_asset = locked_custody.key();
+    option_detail.pool = pool.key();
+    option_detail.custody = custody.key();
+    user.option_index = option_index;
+    user.add_open_option(contract.max_open_options)?;
+    let pool_key = pool.key();
+    option_detail.book_page = params.book_page;
+    pool.add_open_option(
+        pool_key,
+        &mut ctx.accounts.option_book_page,
+        params.book_page,
+        ctx.bumps.option_book_page,
+        option_detail.key(),
     )?;
-    
-    let token_price = OraclePrice::new_from_oracle(custody_oracle_account, curtime, false)?;
 
+    emit!(OptionSold {
+        option_index: option_detail.index,
+        owner: option_detail.owner,
+        strike_price: option_detail.strike_price,
+        strike_exponent: option_detail.strike_exponent,
+        premium: option_detail.premium,
+        amount: option_detail.amount,
+        is_call: option_detail.option_type == 0,
+        expired_date: option_detail.expired_date,
+    });
+
+    Ok(quantity)
+}
+
+/// Premium per option in the pay token, shared by open_option and roll_expired: the model price
+/// of the custody, a spread up to the cap strike for capped options, scaled by the premium
+/// multiplier and bounded by the `min_premium`/`max_premium` slippage of the buyer.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn get_write_premium(
+    custody: &Custody,
+    token_price: &OraclePrice,
+    strike_price: &OraclePrice,
+    period: u64,
+    is_call: bool,
+    profit_cap: Option<u64>,
+    pay_token_price: &OraclePrice,
+    pay_decimals: u8,
+    min_premium: u64,
+    max_premium: u64,
+) -> Result<u64> {
     let oracle_price = token_price.get_price();
-    let period_year = math::checked_as_f64(math::checked_float_div(params.period as f64, 365.0)?)?;
+    let period_year = math::checked_as_f64(math::checked_float_div(period as f64, 365.0)?)?;
 
     msg!("oracle_price: {}", oracle_price);
-    msg!("params.strike: {}", params.strike);
+    msg!("strike: {}", strike_price.price);
     msg!("period_year: {}", period_year);
-    // Calculate Premium in usd using black scholes formula.
-    let premium = OptionDetail::black_scholes(
+    // Calculate Premium in usd using black scholes formula. Capped options pay out like a
+    // spread up to the cap strike, the premium of the option at the cap strike is given back
+    let premium = OptionDetail::get_capped_option_price(
+        custody.pricing_model,
         oracle_price,
-        params.strike,
+        strike_price,
         period_year,
-        custody.key() == locked_custody.key(),
-    );
+        is_call,
+        custody.volatility_bps,
+        profit_cap,
+    )?;
+    let premium = custody.apply_premium_multiplier(premium)?;
     msg!("premium: {}", premium);
 
-    let pay_token_price = OraclePrice::new_from_oracle(pay_custody_oracle_account, curtime, false)?;
-
     // Calculate Premium in pay_toke amount
-    let pay_amount = math::checked_as_u64(
-        math::checked_float_div(premium, pay_token_price.get_price())?
-            * math::checked_powi(10.0, pay_custody.decimals as i32)?,
-    )?;
+    let premium_usd = OptionDetail::get_premium_usd(premium)?;
+    let pay_amount = pay_token_price.get_token_amount(premium_usd, pay_decimals)?;
 
     require_gt!(
         pay_amount,
@@ -88,18 +462,70 @@ pub fn open_option(ctx: Context<OpenOption>, params: &OpenOptionParams) -> Resul
         OptionError::InvalidPayAmountError
     );
 
-    // Add premium to liquidity pool
-    pay_custody.token_owned = math::checked_add(pay_custody.token_owned, params.amount)?;
-    option_detail.premium = pay_amount;
-    option_detail.premium_asset = pay_custody.key();
+    // The premium moves with the oracle until the transaction lands, bounded by the buyer
+    require!(
+        (max_premium == 0 || pay_amount <= max_premium) && pay_amount >= min_premium,
+        OptionError::PremiumSlippageError
+    );
+    Ok(pay_amount)
+}
 
-    let quantity = math::checked_div(params.amount, pay_amount)?;
-    msg!("quantity: {}", quantity);
+/// Transfers the protocol cut of a premium from the buyer to the pay token account of the fee
+/// recipient and records its usd value, shared by open_option and roll_expired.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pay_protocol_fee<'info>(
+    contract: &mut Contract,
+    protocol_fee_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    funding_account: &InterfaceAccount<'info, TokenAccount>,
+    pay_custody_mint: &InterfaceAccount<'info, Mint>,
+    owner: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    protocol_fee: u64,
+    protocol_fee_usd: u64,
+) -> Result<()> {
+    if protocol_fee == 0 {
+        return Ok(());
+    }
+    let Some(protocol_fee_account) = protocol_fee_account else {
+        return err!(OptionError::InvalidFeeAccountError);
+    };
+    require_keys_eq!(
+        protocol_fee_account.owner,
+        contract.fee_recipient,
+        OptionError::InvalidFeeAccountError
+    );
+    require_keys_eq!(
+        protocol_fee_account.mint,
+        pay_custody_mint.key(),
+        OptionError::InvalidMintError
+    );
+
+    contract.transfer_tokens_from_user(
+        funding_account.to_account_info(),
+        protocol_fee_account.to_account_info(),
+        pay_custody_mint,
+        owner,
+        token_program,
+        protocol_fee,
+    )?;
+    contract.record_protocol_fee(protocol_fee_usd)
+}
 
-    let decimals_multiplier = math::checked_powi(10.0, pay_custody.decimals as i32)?;
+/// Locks the collateral of a new option within the utilization limit of the pool and the
+/// locked value cap of the risk group of `locked_custody`, shared by open_option and
+/// roll_expired. `remaining_accounts` are the custodies and oracles of the risk group check.
+pub(crate) fn lock_option_collateral<'info>(
+    contract: &Contract,
+    pool: &Pool,
+    locked_custody: &mut Account<'info, Custody>,
+    locked_amount: u64,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    curtime: i64,
+) -> Result<()> {
+    pool.check_utilization(locked_custody, locked_amount)?;
     locked_custody.token_locked = math::checked_add(
         locked_custody.token_locked,
-        math::checked_as_u64(quantity as f64 * decimals_multiplier)?
+        locked_amount
     )?;
 
     require_gte!(
@@ -108,22 +534,42 @@ pub fn open_option(ctx: Context<OpenOption>, params: &OpenOptionParams) -> Resul
         OptionError::InvalidPoolBalanceError
     );
 
-    // store option data
-    option_detail.amount = params.amount;
-    option_detail.quantity = quantity;
-    option_detail.owner = owner.key();
-    option_detail.index = option_index;
-    option_detail.period = params.period;
-    option_detail.expired_date = params.expired_time as i64;
-    option_detail.purchase_date = curtime as u64;
-    option_detail.option_type = if custody.key() == locked_custody.key() { 0 } else { 1 };
-    option_detail.strike_price = params.strike;
-    option_detail.valid = true;
-    option_detail.locked_asset = locked_custody.key();
-    option_detail.pool = pool.key();
-    option_detail.custody = custody.key();
-    user.option_index = option_index;
+    // Correlated custodies share the locked value cap of their risk group
+    let risk_group_cap = pool.get_risk_group_cap(locked_custody.risk_group);
+    if risk_group_cap > 0 {
+        let risk_group_locked_usd = pool.get_risk_group_locked_usd(
+            contract,
+            &locked_custody.key(),
+            locked_custody,
+            remaining_accounts,
+            curtime,
+        )?;
+        require_gte!(
+            risk_group_cap as u128,
+            risk_group_locked_usd,
+            OptionError::RiskGroupCapReachedError
+        );
+    }
+    Ok(())
+}
 
+/// Rejects writes whose premium is dust or negligible next to the collateral at risk, shared
+/// by open_option and roll_expired.
+pub(crate) fn check_write_premium(
+    contract: &Contract,
+    locked_custody: &Custody,
+    premium_paid_usd: u64,
+    locked_usd: u64,
+) -> Result<()> {
+    require_gte!(
+        premium_paid_usd,
+        contract.min_premium_usd,
+        OptionError::PremiumTooSmallError
+    );
+    require!(
+        locked_custody.is_premium_sufficient(premium_paid_usd, locked_usd)?,
+        OptionError::PremiumTooSmallError
+    );
     Ok(())
 }
 
@@ -134,7 +580,7 @@ pub struct OpenOption<'info> {
     pub owner: Signer<'info>,
 
     #[account(mut)]
-    pub funding_account: Box<Account<'info, TokenAccount>>,
+    pub funding_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: empty PDA, authority for token accounts
     #[account(
@@ -144,6 +590,7 @@ pub struct OpenOption<'info> {
     pub transfer_authority: AccountInfo<'info>,
 
     #[account(
+        mut,
         seeds = [b"contract"],
         bump = contract.bump
     )]
@@ -186,12 +633,24 @@ pub struct OpenOption<'info> {
       payer = owner,
       space=OptionDetail::LEN,
       seeds = [b"option", owner.key().as_ref(), 
-            (user.option_index+1).to_le_bytes().as_ref(),
+            // wraps only at u64::MAX, where next_option_index() rejects the instruction
+            user.option_index.wrapping_add(1).to_le_bytes().as_ref(),
             pool.key().as_ref(), custody.key().as_ref()],
         bump
     )]
     pub option_detail: Box<Account<'info, OptionDetail>>,
 
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OptionBookPage::LEN,
+        seeds = [b"option_book",
+                 pool.key().as_ref(),
+                 params.book_page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+
     #[account(
         mut,
         seeds = [b"custody",
@@ -208,7 +667,7 @@ pub struct OpenOption<'info> {
                  pay_custody.mint.key().as_ref()],
         bump
     )]
-    pub pay_custody_token_account: Box<Account<'info, TokenAccount>>,
+    pub pay_custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECK: oracle account for the position token
     #[account(
@@ -224,12 +683,108 @@ pub struct OpenOption<'info> {
         bump = locked_custody.bump
     )]
     pub locked_custody: Box<Account<'info, Custody>>, // locked asset
+
+    /// CHECK: oracle account for the locked token
+    #[account(
+        constraint = locked_custody_oracle_account.key() == locked_custody.oracle
+    )]
+    pub locked_custody_oracle_account: AccountInfo<'info>,
+    #[account(mut)]
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub pay_custody_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
-    pub pay_custody_mint: Box<Account<'info, Mint>>,
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // secondary premium pay asset, only required when premium_split_bps > 0
     #[account(mut)]
-    pub locked_custody_mint: Box<Account<'info, Mint>>,
-    pub token_program: Program<'info, Token>,
+    pub secondary_funding_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 secondary_pay_custody.mint.as_ref()],
+        bump = secondary_pay_custody.bump
+    )]
+    pub secondary_pay_custody: Option<Box<Account<'info, Custody>>>,
+
+    #[account(mut)]
+    pub secondary_pay_custody_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: oracle account for the secondary pay token, checked against secondary_pay_custody
+    pub secondary_pay_custody_oracle_account: Option<UncheckedAccount<'info>>,
+
+    pub secondary_pay_custody_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    // pay token account of contract.fee_recipient, only required when the protocol fee is non zero
+    #[account(mut)]
+    pub protocol_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usd_price(price: u64) -> OraclePrice {
+        OraclePrice::new(price * 100_000_000, -8)
+    }
+
+    fn write_premium(
+        profit_cap: Option<u64>,
+        min_premium: u64,
+        max_premium: u64,
+    ) -> Result<u64> {
+        let custody = Custody {
+            volatility_bps: 8000,
+            premium_multiplier_bps: Contract::BPS_POWER as u64,
+            ..Default::default()
+        };
+        get_write_premium(
+            &custody,
+            &usd_price(105),
+            &OraclePrice::new(10_000, -2),
+            30,
+            true,
+            profit_cap,
+            &usd_price(1),
+            6,
+            min_premium,
+            max_premium,
+        )
+    }
+
+    #[test]
+    fn write_premiums_are_bounded_by_the_buyer_slippage() {
+        let pay_amount = write_premium(None, 0, 0).unwrap();
+        assert!(write_premium(None, pay_amount, pay_amount).is_ok());
+        assert!(write_premium(None, 0, pay_amount - 1).is_err());
+        assert!(write_premium(None, pay_amount + 1, 0).is_err());
+    }
+
+    #[test]
+    fn capped_writes_charge_less_than_uncapped_ones() {
+        let capped = write_premium(Some(25_000_000), 0, 0).unwrap();
+        assert!(capped > 0 && capped < write_premium(None, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn write_premiums_respect_the_floor_and_the_collateral_ratio() {
+        let contract = Contract {
+            min_premium_usd: 1_000_000,
+            ..Default::default()
+        };
+        let locked_custody = Custody {
+            min_premium_to_collateral_bps: 100,
+            ..Default::default()
+        };
+        assert!(check_write_premium(&contract, &locked_custody, 2_000_000, 200_000_000).is_ok());
+        // below the dust floor
+        assert!(check_write_premium(&contract, &locked_custody, 999_999, 1_000_000).is_err());
+        // below 1% of the collateral
+        assert!(check_write_premium(&contract, &locked_custody, 2_000_000, 300_000_000).is_err());
+    }
+}
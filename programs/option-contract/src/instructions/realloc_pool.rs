@@ -54,7 +54,9 @@ pub struct RealocPool<'info> {
     #[account(
         mut,
         realloc = Pool::LEN + (pool.custodies.len() + 1) * std::mem::size_of::<Pubkey>() +
-        (pool.ratios.len() + 1) * std::mem::size_of::<TokenRatios>(),
+        (pool.ratios.len() + 1) * std::mem::size_of::<TokenRatios>() +
+        pool.allowed_premium_mints.len() * std::mem::size_of::<Pubkey>() +
+        pool.keepers.len() * std::mem::size_of::<Pubkey>(),
         realloc::payer = signer,
         realloc::zero = false,
         seeds = [b"pool", params.pool_name.as_bytes()],
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RebalanceAckParams {
+    pub pool_name: String,
+}
+
+pub fn rebalance_ack<'info>(
+    ctx: Context<'_, '_, '_, 'info, RebalanceAck<'info>>,
+    params: &RebalanceAckParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::RebalanceAck, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // start a new premium period, resuming writes
+    let custody = ctx.accounts.custody.as_mut();
+    custody.premium_since_rebalance = 0;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: RebalanceAckParams)]
+pub struct RebalanceAck<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
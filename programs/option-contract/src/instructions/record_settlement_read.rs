@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::OptionError,
+    math,
+    state::{Contract, Custody, OptionDetail, OraclePrice, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RecordSettlementReadParams {
+    pub user: Pubkey,
+    pub option_index: u64,
+    pub pool_name: String,
+}
+
+pub fn record_settlement_read(
+    ctx: Context<RecordSettlementRead>,
+    _params: &RecordSettlementReadParams,
+) -> Result<()> {
+    let custody = &ctx.accounts.custody;
+    let custody_oracle_account = &ctx.accounts.custody_oracle_account;
+    let option_detail = &mut ctx.accounts.option_detail;
+
+    require!(
+        option_detail.valid && option_detail.exercised == 0,
+        OptionError::OptionNotValid
+    );
+    require_gt!(custody.settlement_window_sec, 0, OptionError::InvalidTimeError);
+
+    // Only prints within the window around expiry count, each of them once
+    let publish_time = OraclePrice::get_publish_time(custody_oracle_account)?;
+    require_gte!(
+        custody.settlement_window_sec,
+        math::checked_sub(publish_time, option_detail.expired_date)?.abs(),
+        OptionError::InvalidTimeError
+    );
+    require_gt!(
+        publish_time,
+        option_detail.settlement_last_publish,
+        OptionError::InvalidTimeError
+    );
+
+    let curtime = ctx.accounts.contract.get_time()?;
+    let token_price = ctx
+        .accounts
+        .contract
+        .get_oracle_price(custody_oracle_account, curtime)?
+        .scale_to_exponent(option_detail.strike_exponent)?;
+
+    option_detail.settlement_price_sum =
+        math::checked_add(option_detail.settlement_price_sum, token_price.price as u128)?;
+    option_detail.settlement_reads = math::checked_add(option_detail.settlement_reads, 1)?;
+    option_detail.settlement_last_publish = publish_time;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: RecordSettlementReadParams)]
+pub struct RecordSettlementRead<'info> {
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle_account.key() == custody.oracle
+    )]
+    pub custody_oracle_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"option", params.user.as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
     errors::PoolError,
@@ -81,7 +81,9 @@ pub struct RemoveCustody<'info> {
     #[account(
         mut,
         realloc = Pool::LEN + (pool.custodies.len() + 1) * std::mem::size_of::<Pubkey>() +
-        (pool.ratios.len() + 1) * std::mem::size_of::<TokenRatios>(),
+        (pool.ratios.len() + 1) * std::mem::size_of::<TokenRatios>() +
+        pool.allowed_premium_mints.len() * std::mem::size_of::<Pubkey>() +
+        pool.keepers.len() * std::mem::size_of::<Pubkey>(),
         realloc::payer = signer,
         realloc::zero = false,
         seeds = [b"pool", params.pool_name.as_bytes()],
@@ -105,7 +107,7 @@ pub struct RemoveCustody<'info> {
                  custody_token_mint.key().as_ref()],
         bump = custody.token_account_bump
     )]
-    pub custody_token_account: Account<'info, TokenAccount>,
+    pub custody_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: empty PDA, authority for token accounts
     #[account(
@@ -114,7 +116,7 @@ pub struct RemoveCustody<'info> {
     )]
     pub transfer_authority: AccountInfo<'info>,
 
-    pub custody_token_mint: Box<Account<'info, Mint>>,
-    token_program: Program<'info, Token>,
+    pub custody_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 }
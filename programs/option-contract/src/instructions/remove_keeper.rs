@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Pool, TokenRatios,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RemoveKeeperParams {
+    pub keeper: Pubkey,
+    pub pool_name: String,
+}
+
+pub fn remove_keeper<'info>(
+    ctx: Context<'_, '_, '_, 'info, RemoveKeeper<'info>>,
+    params: &RemoveKeeperParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::RemoveKeeper, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update pool data
+    let pool = ctx.accounts.pool.as_mut();
+    let Some(keeper_index) = pool.keepers.iter().position(|keeper| *keeper == params.keeper) else {
+        return Err(ProgramError::InvalidArgument.into());
+    };
+    pool.keepers.remove(keeper_index);
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: RemoveKeeperParams)]
+pub struct RemoveKeeper<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        realloc = Pool::LEN + pool.custodies.len() * std::mem::size_of::<Pubkey>() +
+        pool.ratios.len() * std::mem::size_of::<TokenRatios>() +
+        pool.allowed_premium_mints.len() * std::mem::size_of::<Pubkey>() +
+        pool.keepers.len().saturating_sub(1) * std::mem::size_of::<Pubkey>(),
+        realloc::payer = signer,
+        realloc::zero = false,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    system_program: Program<'info, System>,
+}
@@ -2,13 +2,16 @@
 
 use {
     crate::{
-        errors::ContractError, math, state::{
+        errors::{ContractError, PoolError}, math, state::{
             custody::Custody,
-            oracle::OraclePrice, Contract, Pool,
+            Contract, Pool,
         }
     },
     anchor_lang::prelude::*,
-    anchor_spl::token::{Mint, Token, TokenAccount},
+    anchor_spl::{
+        token::{spl_token::native_mint, Mint, Token, TokenAccount},
+        token_interface::{self, TokenInterface},
+    },
 };
 
 #[derive(Accounts)]
@@ -19,10 +22,10 @@ pub struct RemoveLiquidity<'info> {
 
     #[account(
         mut,
-        constraint = receiving_account.mint == custody.mint,
+        constraint = receiving_account.mint == custody.mint @ PoolError::InvalidCustodyTokenError,
         has_one = owner
     )]
-    pub receiving_account: Box<Account<'info, TokenAccount>>,
+    pub receiving_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(
         mut,
@@ -74,7 +77,7 @@ pub struct RemoveLiquidity<'info> {
                  custody_mint.key().as_ref()],
         bump = custody.token_account_bump
     )]
-    pub custody_token_account: Box<Account<'info, TokenAccount>>,
+    pub custody_token_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     #[account(
         mut,
@@ -85,9 +88,10 @@ pub struct RemoveLiquidity<'info> {
     pub lp_token_mint: Box<Account<'info, Mint>>,
 
     #[account(mut)]
-    pub custody_mint: Box<Account<'info, Mint>>,
+    pub custody_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
-    token_program: Program<'info, Token>,
+    token_program: Program<'info, Token>, // lp token program
+    custody_token_program: Interface<'info, TokenInterface>,
     // remaining accounts:
     //   pool.tokens.len() custody accounts (read-only, unsigned)
     //   pool.tokens.len() custody oracles (read-only, unsigned)
@@ -97,7 +101,18 @@ pub struct RemoveLiquidity<'info> {
 pub struct RemoveLiquidityParams {
     pub lp_amount_in: u64,
     pub min_amount_out: u64,
-    pub pool_name: String
+    pub pool_name: String,
+    pub unwrap_on_withdraw: bool, // close the WSOL receiving account to return native SOL
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub custody: Pubkey,
+    pub lp_amount_in: u64,
+    pub fee_amount: u64,
+    pub amount_out: u64,
 }
 
 pub fn remove_liquidity<'info>(
@@ -124,20 +139,20 @@ pub fn remove_liquidity<'info>(
     pool.aum_usd =
         pool.get_assets_under_management_usd(ctx.remaining_accounts, curtime)?;
 
-    let token_price = OraclePrice::new_from_oracle(
+    let token_price = contract.get_oracle_price(
         &ctx.accounts.custody_oracle_account.to_account_info(),
         curtime,
-        false,
     )?;
 
     let pool_amount_usd =
         pool.get_assets_under_management_usd(ctx.remaining_accounts, curtime)?;
 
     // compute amount of tokens to return
-    let remove_amount_usd = math::checked_as_u64(math::checked_div(
-        math::checked_mul(pool_amount_usd, params.lp_amount_in as u128)?,
-        ctx.accounts.lp_token_mint.supply as u128,
-    )?)?;
+    let remove_amount_usd = Pool::get_lp_value_usd(
+        params.lp_amount_in,
+        ctx.accounts.lp_token_mint.supply,
+        pool_amount_usd,
+    )?;
 
     let remove_amount = token_price.get_token_amount(remove_amount_usd, custody.decimals)?;
 
@@ -155,26 +170,39 @@ pub fn remove_liquidity<'info>(
         ContractError::InsufficientAmountReturned
     );
 
-    // check pool constraints
+    // check pool constraints, the fee stays in the custody as it does on deposits
     msg!("Check pool constraints");
-    let withdrawal_amount = math::checked_add(transfer_amount, fee_amount)?;
     require!(
-        pool.check_token_ratio(token_id, 0, withdrawal_amount, custody, &token_price)?,
+        pool.check_token_ratio(token_id, 0, transfer_amount, custody, &token_price)?,
         ContractError::TokenRatioOutOfRange
     );
 
     require!(
-        math::checked_sub(custody.token_owned, custody.token_locked)? >= withdrawal_amount,
+        math::checked_sub(custody.token_owned, custody.token_locked)? >= transfer_amount,
         ContractError::CustodyAmountLimit
     );
 
+    // profits owed to option holders are not LP funds
+    require!(
+        custody.get_withdrawable_amount()? >= transfer_amount,
+        PoolError::InvalidWithdrawError
+    );
+
+    // the token account must hold what the custody accounts for
+    require_gte!(
+        ctx.accounts.custody_token_account.amount,
+        transfer_amount,
+        PoolError::InvalidPoolBalanceError
+    );
+
     // transfer tokens
     msg!("Transfer tokens");
     contract.transfer_tokens(
         ctx.accounts.custody_token_account.to_account_info(),
         ctx.accounts.receiving_account.to_account_info(),
+        &ctx.accounts.custody_mint,
         ctx.accounts.transfer_authority.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.custody_token_program.to_account_info(),
         transfer_amount,
     )?;
 
@@ -188,9 +216,20 @@ pub fn remove_liquidity<'info>(
         params.lp_amount_in,
     )?;
 
+    // Closing the WSOL account returns its whole balance and rent reserve as native SOL
+    if params.unwrap_on_withdraw {
+        require_keys_eq!(custody.mint, native_mint::ID, PoolError::InvalidCustodyTokenError);
+        Contract::close_token_account(
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.receiving_account.to_account_info(),
+            ctx.accounts.custody_token_program.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            &[],
+        )?;
+    }
+
     // update custody stats
-    
-    custody.token_owned = math::checked_sub(custody.token_owned, withdrawal_amount)?;
+    custody.debit_withdrawal(transfer_amount)?;
 
     // update pool stats
     msg!("Update pool stats");
@@ -198,5 +237,14 @@ pub fn remove_liquidity<'info>(
     pool.aum_usd =
         pool.get_assets_under_management_usd(ctx.remaining_accounts, curtime)?;
 
+    emit!(LiquidityRemoved {
+        owner: ctx.accounts.owner.key(),
+        pool: pool.key(),
+        custody: custody.key(),
+        lp_amount_in: params.lp_amount_in,
+        fee_amount,
+        amount_out: transfer_amount,
+    });
+
     Ok(())
 }
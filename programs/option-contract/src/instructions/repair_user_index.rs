@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::OptionError,
+    state::{
+        multisig::{AdminInstruction, Multisig},
+        Contract, OptionDetail, User,
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RepairUserIndexParams {
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct UserIndexRepaired {
+    pub user: Pubkey,
+    pub old_option_index: u64,
+    pub new_option_index: u64,
+}
+
+pub fn repair_user_index<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RepairUserIndex<'info>>,
+    params: &RepairUserIndexParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::RepairUserIndex, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // the index is the highest existing option of the user, so it never drops below one of
+    // the passed accounts and the next write can't collide with them
+    let mut option_index = 0;
+    for account_info in ctx.remaining_accounts.iter() {
+        let option_detail = Account::<OptionDetail>::try_from(account_info)?;
+        require_keys_eq!(option_detail.owner, params.user, OptionError::InvalidOwner);
+        option_index = option_index.max(option_detail.index);
+    }
+
+    // update user data
+    let user = ctx.accounts.user.as_mut();
+    let old_option_index = user.option_index;
+    user.option_index = option_index;
+
+    emit!(UserIndexRepaired {
+        user: params.user,
+        old_option_index,
+        new_option_index: option_index,
+    });
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: RepairUserIndexParams)]
+pub struct RepairUserIndex<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", params.user.as_ref()],
+        bump,
+    )]
+    pub user: Box<Account<'info, User>>,
+    // remaining accounts:
+    //   all option detail accounts of the user (read-only, unsigned)
+}
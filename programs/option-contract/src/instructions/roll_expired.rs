@@ -0,0 +1,564 @@
+use crate::{
+    errors::{ContractError, OptionError},
+    instructions::{check_write_premium, get_write_premium, lock_option_collateral, pay_protocol_fee},
+    math,
+    state::{
+        Contract, Custody, OptionBookPage, OptionDetail, OraclePrice, Pool, SettlementKind, User,
+    },
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RollExpiredParams {
+    pub option_index: u64, // expired option to roll
+    pub strike: u64,       // Strike price of the new option, scaled by the custody strike exponent
+    pub period: u64,       // Number of days from option creation to expiration
+    pub expired_time: u64, // when the new option is expired : Unix epoch time
+    pub pool_name: String,
+    pub max_premium: u64, // max premium per option in the pay token, 0 = any
+    pub min_premium: u64, // min premium per option in the pay token, 0 = any
+}
+
+#[event]
+pub struct OptionRolled {
+    pub option_index: u64, // expired option
+    pub new_option_index: u64,
+    pub owner: Pubkey,
+    pub strike_price: u64, // of the new option, scaled by strike_exponent
+    pub strike_exponent: i32,
+    pub premium: u64,
+    pub amount: u64,
+    pub expired_date: i64,
+}
+
+// The new option keeps the quantity, profit cap and settlement of the rolled one and is written
+// like open_option writes it: priced, charged and collateralized at the new strike.
+// remaining accounts: the backup oracles of the custody with their settlement prints for the
+// rolled option, see Custody::get_settlement_backup_len, then as for open_option when the
+// locked custody has a risk group cap
+pub fn roll_expired<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RollExpired<'info>>,
+    params: &RollExpiredParams,
+) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let token_program = &ctx.accounts.token_program;
+    let funding_account = &ctx.accounts.funding_account;
+    let contract = &mut ctx.accounts.contract;
+    let user = &mut ctx.accounts.user;
+    let pool = &mut ctx.accounts.pool;
+    let custody = &mut ctx.accounts.custody;
+    let custody_oracle_account = &ctx.accounts.custody_oracle_account;
+    let locked_custody = &mut ctx.accounts.locked_custody;
+    let locked_custody_oracle_account = &ctx.accounts.locked_custody_oracle_account;
+    let pay_custody = &mut ctx.accounts.pay_custody;
+    let pay_custody_token_account = &ctx.accounts.pay_custody_token_account;
+    let pay_custody_oracle_account = &ctx.accounts.pay_custody_oracle_account;
+    let pay_custody_mint = &ctx.accounts.pay_custody_mint;
+    let old_option_detail = &mut ctx.accounts.old_option_detail;
+    let option_detail = &mut ctx.accounts.option_detail;
+
+    // Sales halt in an emergency, exercises and withdrawals stay open for users to exit
+    require!(!contract.paused, ContractError::ContractPaused);
+
+    require_gte!(user.option_index, params.option_index);
+    require_eq!(
+        old_option_detail.exercised,
+        0,
+        OptionError::OptionAlreadyExercised
+    );
+    require!(old_option_detail.valid, OptionError::OptionNotValid);
+    require!(
+        !old_option_detail.settlement_frozen,
+        OptionError::SettlementFrozenError
+    );
+    require_keys_eq!(
+        old_option_detail.owner,
+        owner.key(),
+        OptionError::InvalidOwner
+    );
+    require_keys_eq!(locked_custody.key(), old_option_detail.locked_asset);
+
+    let curtime = contract.get_time()?;
+
+    // Writes are paused once the premium cap is hit until admins acknowledge a rebalance
+    require!(
+        !pay_custody.is_premium_cap_reached()?,
+        OptionError::PremiumCapReachedError
+    );
+
+    // Premiums can only be paid in the currencies the pool accepts
+    require!(
+        pool.is_premium_mint_allowed(&pay_custody.mint),
+        OptionError::PremiumMintNotAllowed
+    );
+
+    // Settlement math between the tokens stays within safe precision
+    pool.check_decimals_diff(pay_custody.decimals, locked_custody.decimals)?;
+    pool.check_decimals_diff(custody.decimals, locked_custody.decimals)?;
+
+    // Old option can only be rolled once expired, new one must expire in the future
+    require_gte!(
+        curtime,
+        old_option_detail.expired_date,
+        OptionError::InvalidTimeError
+    );
+    OptionDetail::validate_expiry(params.period, params.expired_time, curtime)?;
+
+    // Each custody is priced by the oracle program it was set up with
+    custody.check_oracle_kind(custody_oracle_account)?;
+    pay_custody.check_oracle_kind(pay_custody_oracle_account)?;
+    locked_custody.check_oracle_kind(locked_custody_oracle_account)?;
+    let backup_len = custody.get_settlement_backup_len();
+    if ctx.remaining_accounts.len() < backup_len {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+    let (backup_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(backup_len);
+    // the new option is priced off the median of the same feeds
+    let backup_oracles: Vec<AccountInfo> = backup_accounts.iter().step_by(2).cloned().collect();
+    let token_price =
+        custody.get_oracle_price(contract, curtime, custody_oracle_account, &backup_oracles)?;
+
+    // Writes pause for a cooldown after a sharp move of the custody oracle, tracked on the
+    // copy written back last when custody is also the pay or locked custody
+    let price_move_custody =
+        Custody::last_written(Custody::last_written(custody, pay_custody), locked_custody);
+    price_move_custody.update_price_move(&token_price, curtime)?;
+    require!(
+        !price_move_custody.is_write_paused(curtime),
+        OptionError::WriteCooldownError
+    );
+
+    // Only options that expired worthless can be rolled
+    let is_call = old_option_detail.option_type == 0;
+    check_expired_worthless(
+        old_option_detail,
+        custody,
+        custody_oracle_account,
+        &ctx.accounts.settlement_oracle,
+        backup_accounts,
+        curtime,
+    )?;
+
+    // Expire old option and free its collateral for the new one
+    old_option_detail.exercised = curtime as u64;
+    old_option_detail.valid = false;
+    old_option_detail.profit = 0;
+    old_option_detail.claimed = 0;
+    locked_custody.unlock_funds(old_option_detail.amount)?;
+
+    let quantity = old_option_detail.quantity;
+    let profit_cap = old_option_detail.profit_cap;
+    // Physical delivery needs the whole underlying locked, as uncapped calls do
+    require!(
+        old_option_detail.settlement == SettlementKind::Cash || profit_cap.is_none(),
+        OptionError::InvalidSettlementError
+    );
+
+    let strike_price = OraclePrice::new(params.strike, custody.strike_exponent);
+    let pay_token_price = contract.get_oracle_price(pay_custody_oracle_account, curtime)?;
+    let pay_amount = get_write_premium(
+        custody,
+        &token_price,
+        &strike_price,
+        params.period,
+        is_call,
+        profit_cap,
+        &pay_token_price,
+        pay_custody.decimals,
+        params.min_premium,
+        params.max_premium,
+    )?;
+
+    // Same size as the rolled option, the protocol cut goes to the fee recipient
+    let premium_amount = math::checked_mul(pay_amount, quantity)?;
+    let protocol_fee = contract.get_protocol_fee(premium_amount)?;
+    let protocol_fee = math::checked_sub(
+        protocol_fee,
+        contract.take_maker_rebate(
+            protocol_fee,
+            pay_token_price.get_asset_amount_usd(protocol_fee, pay_custody.decimals)?,
+            curtime,
+        )?,
+    )?;
+    let pool_pay_amount = math::checked_sub(premium_amount, protocol_fee)?;
+    require_gte!(
+        funding_account.amount,
+        premium_amount,
+        OptionError::InvalidSignerBalanceError
+    );
+
+    contract.transfer_tokens_from_user(
+        funding_account.to_account_info(),
+        pay_custody_token_account.to_account_info(),
+        pay_custody_mint,
+        owner.to_account_info(),
+        token_program.to_account_info(),
+        pool_pay_amount,
+    )?;
+    pay_protocol_fee(
+        contract,
+        ctx.accounts.protocol_fee_account.as_deref(),
+        funding_account,
+        pay_custody_mint,
+        owner.to_account_info(),
+        token_program.to_account_info(),
+        protocol_fee,
+        pay_token_price.get_asset_amount_usd(protocol_fee, pay_custody.decimals)?,
+    )?;
+
+    // Add premium to liquidity pool, on the copy written back last when pay_custody is also
+    // the locked custody
+    Custody::last_written(pay_custody, locked_custody).collect_premium(pool_pay_amount)?;
+    pool.record_premium(pay_token_price.get_asset_amount_usd(pool_pay_amount, pay_custody.decimals)?)?;
+
+    // Lock the max loss at the new strike plus the collateral buffer, in whole lots
+    let max_loss = OptionDetail::get_max_loss(
+        quantity,
+        &strike_price,
+        is_call,
+        profit_cap,
+        locked_custody.decimals,
+    )?;
+    let locked_amount =
+        locked_custody.apply_lot_size(locked_custody.apply_collateral_buffer(max_loss)?)?;
+    lock_option_collateral(
+        contract,
+        pool,
+        locked_custody,
+        locked_amount,
+        remaining_accounts,
+        curtime,
+    )?;
+
+    let locked_token_price =
+        contract.get_oracle_price(locked_custody_oracle_account, curtime)?;
+    check_write_premium(
+        contract,
+        locked_custody,
+        pay_token_price.get_asset_amount_usd(premium_amount, pay_custody.decimals)?,
+        locked_token_price.get_asset_amount_usd(locked_amount, locked_custody.decimals)?,
+    )?;
+
+    // store new option data
+    let option_index = user.next_option_index()?;
+    option_detail.amount = locked_amount;
+    option_detail.quantity = quantity;
+    option_detail.max_profit = profit_cap.map(|_| max_loss);
+    option_detail.profit_cap = profit_cap;
+    option_detail.owner = owner.key();
+    option_detail.index = option_index;
+    option_detail.period = params.period;
+    option_detail.expired_date = params.expired_time as i64;
+    option_detail.purchase_date = curtime as u64;
+    option_detail.created_slot = contract.get_slot()?;
+    option_detail.option_type = old_option_detail.option_type;
+    option_detail.strike_price = params.strike;
+    option_detail.strike_exponent = custody.strike_exponent;
+    option_detail.breakeven_price = option_detail.get_breakeven_price(&OraclePrice::new(
+        pay_token_price.get_asset_amount_usd(pay_amount, pay_custody.decimals)?,
+        -(Contract::USD_DECIMALS as i32),
+    ))?;
+    option_detail.premium = pay_amount;
+    option_detail.premium_asset = pay_custody.key();
+    option_detail.valid = true;
+    option_detail.is_american = old_option_detail.is_american;
+    option_detail.settlement = old_option_detail.settlement;
+    option_detail.locked_asset = locked_custody.key();
+    option_detail.pool = pool.key();
+    option_detail.custody = custody.key();
+    user.option_index = option_index;
+    option_detail.book_page = old_option_detail.book_page;
+    pool.replace_open_option(
+        &mut ctx.accounts.option_book_page,
+        &old_option_detail.key(),
+        option_detail.key(),
+    );
+
+    emit!(OptionRolled {
+        option_index: old_option_detail.index,
+        new_option_index: option_detail.index,
+        owner: option_detail.owner,
+        strike_price: option_detail.strike_price,
+        strike_exponent: option_detail.strike_exponent,
+        premium: option_detail.premium,
+        amount: option_detail.amount,
+        expired_date: option_detail.expired_date,
+    });
+
+    Ok(())
+}
+
+/// Rejects options that expired in the money, judged at the expiry price settlement uses and
+/// not at the live price, which may have moved since.
+fn check_expired_worthless<'info>(
+    option_detail: &OptionDetail,
+    custody: &Custody,
+    custody_oracle: &AccountInfo<'info>,
+    settlement_oracle: &AccountInfo<'info>,
+    backup_accounts: &[AccountInfo<'info>],
+    current_time: i64,
+) -> Result<()> {
+    // Averaged settlement needs all reads of the window around expiry
+    if custody.settlement_window_sec > 0 {
+        require_gt!(
+            current_time,
+            math::checked_add(option_detail.expired_date, custody.settlement_window_sec)?,
+            OptionError::InvalidTimeError
+        );
+    }
+    let expiry_price = option_detail.get_expiry_price(
+        custody,
+        custody_oracle,
+        settlement_oracle,
+        backup_accounts,
+    )?;
+    // At the money expires worthless, see OptionDetail::is_in_the_money
+    require!(
+        !option_detail.is_in_the_money(&expiry_price),
+        OptionError::InvalidPriceRequirementError
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: RollExpiredParams)]
+pub struct RollExpired<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub funding_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool",
+                 params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(mut)]
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub pay_custody_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub locked_custody_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody_mint.key().as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>, // Target price asset
+
+    /// CHECK: oracle account for the position token
+    #[account(
+        constraint = custody_oracle_account.key() == custody.oracle
+    )]
+    pub custody_oracle_account: AccountInfo<'info>,
+
+    /// CHECK: price update of the custody oracle feed published at expiry of the rolled option,
+    /// validated on settlement
+    pub settlement_oracle: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+    )]
+    pub user: Box<Account<'info, User>>,
+
+    #[account(
+        mut,
+        seeds = [b"option", owner.key().as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump
+    )]
+    pub old_option_detail: Box<Account<'info, OptionDetail>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = OptionDetail::LEN,
+        seeds = [b"option", owner.key().as_ref(),
+                // wraps only at u64::MAX, where next_option_index() rejects the instruction
+                user.option_index.wrapping_add(1).to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+
+    #[account(
+        mut,
+        seeds = [b"option_book",
+                 pool.key().as_ref(),
+                 old_option_detail.book_page.to_le_bytes().as_ref()],
+        bump = option_book_page.bump
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 pay_custody_mint.key().as_ref()],
+        bump = pay_custody.bump
+    )]
+    pub pay_custody: Box<Account<'info, Custody>>, // premium pay asset
+
+    #[account(
+        mut,
+        seeds = [b"custody_token_account",
+                 pool.key().as_ref(),
+                 pay_custody.mint.key().as_ref()],
+        bump
+    )]
+    pub pay_custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: oracle account for the premium pay token
+    #[account(
+        constraint = pay_custody_oracle_account.key() == pay_custody.oracle
+    )]
+    pub pay_custody_oracle_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 locked_custody_mint.key().as_ref()],
+        bump = locked_custody.bump
+    )]
+    pub locked_custody: Box<Account<'info, Custody>>, // locked asset
+
+    /// CHECK: oracle account for the locked token
+    #[account(
+        constraint = locked_custody_oracle_account.key() == locked_custody.oracle
+    )]
+    pub locked_custody_oracle_account: AccountInfo<'info>,
+
+    // pay token account of contract.fee_recipient, only required when the protocol fee is non zero
+    #[account(mut)]
+    pub protocol_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyth_solana_receiver_sdk::price_update::{
+        PriceFeedMessage, PriceUpdateV2, VerificationLevel,
+    };
+
+    const EXPIRY: i64 = 1_700_000_000;
+
+    // PriceUpdateV2 account of the Pyth Receiver program, published `publish_time` with the
+    // previous print of the feed before expiry
+    struct Print {
+        key: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl Print {
+        fn new(price: u64, publish_time: i64) -> Self {
+            let price_update = PriceUpdateV2 {
+                write_authority: Pubkey::default(),
+                verification_level: VerificationLevel::Full,
+                price_message: PriceFeedMessage {
+                    feed_id: [1; 32],
+                    price: (price * 100_000_000) as i64,
+                    conf: 0,
+                    exponent: -8,
+                    publish_time,
+                    prev_publish_time: EXPIRY - 1,
+                    ema_price: 0,
+                    ema_conf: 0,
+                },
+                posted_slot: 0,
+            };
+            let mut data = vec![0; 8];
+            price_update.serialize(&mut data).unwrap();
+            Print {
+                key: Pubkey::new_unique(),
+                lamports: 1,
+                data,
+            }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                false,
+                &mut self.lamports,
+                &mut self.data,
+                &pyth_solana_receiver_sdk::ID,
+                false,
+                0,
+            )
+        }
+    }
+
+    // call struck at 100
+    fn expired_call() -> OptionDetail {
+        OptionDetail {
+            quantity: 1,
+            strike_price: 10_000,
+            strike_exponent: -2,
+            option_type: 0,
+            expired_date: EXPIRY,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn calls_that_expired_in_the_money_are_not_rolled_after_the_price_drops() {
+        let option_detail = expired_call();
+        let mut live = Print::new(90, EXPIRY + 600);
+        let mut settlement = Print::new(110, EXPIRY + 3);
+        let (live, settlement) = (live.info(), settlement.info());
+
+        assert!(!option_detail.is_in_the_money(&OraclePrice::new(9_000_000_000, -8)));
+        assert!(check_expired_worthless(
+            &option_detail,
+            &Custody::default(),
+            &live,
+            &settlement,
+            &[],
+            EXPIRY + 600
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn calls_that_expired_out_of_the_money_are_rolled_after_the_price_rises() {
+        let option_detail = expired_call();
+        let mut live = Print::new(120, EXPIRY + 600);
+        let mut settlement = Print::new(95, EXPIRY + 3);
+        let (live, settlement) = (live.info(), settlement.info());
+
+        assert!(option_detail.is_in_the_money(&OraclePrice::new(12_000_000_000, -8)));
+        assert!(check_expired_worthless(
+            &option_detail,
+            &Custody::default(),
+            &live,
+            &settlement,
+            &[],
+            EXPIRY + 600
+        )
+        .is_ok());
+    }
+}
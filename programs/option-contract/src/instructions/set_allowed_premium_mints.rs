@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Pool, TokenRatios,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetAllowedPremiumMintsParams {
+    pub allowed_premium_mints: Vec<Pubkey>, // empty allows every custody of the pool
+    pub pool_name: String,
+}
+
+pub fn set_allowed_premium_mints<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetAllowedPremiumMints<'info>>,
+    params: &SetAllowedPremiumMintsParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetAllowedPremiumMints, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update pool data
+    let pool = ctx.accounts.pool.as_mut();
+    pool.allowed_premium_mints = params.allowed_premium_mints.clone();
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetAllowedPremiumMintsParams)]
+pub struct SetAllowedPremiumMints<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        realloc = Pool::LEN + pool.custodies.len() * std::mem::size_of::<Pubkey>() +
+        pool.ratios.len() * std::mem::size_of::<TokenRatios>() +
+        params.allowed_premium_mints.len() * std::mem::size_of::<Pubkey>() +
+        pool.keepers.len() * std::mem::size_of::<Pubkey>(),
+        realloc::payer = signer,
+        realloc::zero = false,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    system_program: Program<'info, System>,
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetAutoExerciseReserveParams {
+    pub auto_exercise_reserve_bps: u64, // share of auto exercised profit kept in the reserve
+}
+
+pub fn set_auto_exercise_reserve<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetAutoExerciseReserve<'info>>,
+    params: &SetAutoExerciseReserveParams,
+) -> Result<u8> {
+    // validate inputs
+    if params.auto_exercise_reserve_bps > Contract::MAX_AUTO_EXERCISE_RESERVE_BPS {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetAutoExerciseReserve, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update contract data
+    let contract = ctx.accounts.contract.as_mut();
+    contract.auto_exercise_reserve_bps = params.auto_exercise_reserve_bps;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetAutoExerciseReserveParams)]
+pub struct SetAutoExerciseReserve<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetBackupOraclesParams {
+    pub backup_oracles: [Pubkey; 2], // default key leaves a slot unused
+    pub min_oracle_feeds: u8,        // 0 prices off the primary oracle only
+    pub pool_name: String,
+}
+
+pub fn set_backup_oracles<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetBackupOracles<'info>>,
+    params: &SetBackupOraclesParams,
+) -> Result<u8> {
+    // validate inputs, the required feeds must be configured
+    let feed_count = 1 + params
+        .backup_oracles
+        .iter()
+        .filter(|oracle| **oracle != Pubkey::default())
+        .count();
+    if params.min_oracle_feeds as usize > feed_count {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetBackupOracles, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody data
+    let custody = ctx.accounts.custody.as_mut();
+    custody.backup_oracles = params.backup_oracles;
+    custody.min_oracle_feeds = params.min_oracle_feeds;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetBackupOraclesParams)]
+pub struct SetBackupOracles<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
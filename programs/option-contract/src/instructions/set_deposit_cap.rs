@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetDepositCapParams {
+    pub max_deposit_per_lp_usd: u64, // 0 = no cap
+    pub max_total_deposits_usd: u64, // 0 = no cap
+    pub pool_name: String,
+}
+
+pub fn set_deposit_cap<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetDepositCap<'info>>,
+    params: &SetDepositCapParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetDepositCap, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update pool data
+    let pool = ctx.accounts.pool.as_mut();
+    pool.max_deposit_per_lp_usd = params.max_deposit_per_lp_usd;
+    pool.max_total_deposits_usd = params.max_total_deposits_usd;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetDepositCapParams)]
+pub struct SetDepositCap<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetEarlyExercisePenaltyParams {
+    pub early_exercise_penalty_bps: u64,
+    pub pool_name: String,
+}
+
+pub fn set_early_exercise_penalty<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetEarlyExercisePenalty<'info>>,
+    params: &SetEarlyExercisePenaltyParams,
+) -> Result<u8> {
+    // validate inputs, the penalty can't exceed the payout
+    if params.early_exercise_penalty_bps > Contract::BPS_POWER as u64 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetEarlyExercisePenalty, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody data
+    let custody = ctx.accounts.custody.as_mut();
+    custody.early_exercise_penalty_bps = params.early_exercise_penalty_bps;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetEarlyExercisePenaltyParams)]
+pub struct SetEarlyExercisePenalty<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
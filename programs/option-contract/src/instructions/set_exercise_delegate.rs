@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::state::User;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetExerciseDelegateParams {
+    pub delegate: Option<Pubkey>, // None revokes the current delegate
+    pub delegate_payout: bool, // payout may go to a token account of the delegate
+}
+
+pub fn set_exercise_delegate(
+    ctx: Context<SetExerciseDelegate>,
+    params: &SetExerciseDelegateParams,
+) -> Result<()> {
+    let user = &mut ctx.accounts.user;
+    user.exercise_delegate = params.delegate;
+    user.delegate_payout = params.delegate.is_some() && params.delegate_payout;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetExerciseDelegate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+    )]
+    pub user: Box<Account<'info, User>>,
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::OptionError,
+    state::{Custody, OptionDetail, Pool},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SetExerciseTriggerParams {
+    pub option_index: u64,
+    pub pool_name: String,
+    pub trigger_price: Option<u64>, // scaled by the option strike exponent, None removes it
+}
+
+pub fn set_exercise_trigger(
+    ctx: Context<SetExerciseTrigger>,
+    params: &SetExerciseTriggerParams,
+) -> Result<()> {
+    let option_detail = &mut ctx.accounts.option_detail;
+
+    require_eq!(
+        option_detail.exercised,
+        0,
+        OptionError::OptionAlreadyExercised
+    );
+    require!(option_detail.valid, OptionError::OptionNotValid);
+
+    // A take-profit trigger has to be in the money
+    if let Some(trigger_price) = params.trigger_price {
+        if option_detail.option_type == 0 {
+            require_gt!(
+                trigger_price,
+                option_detail.strike_price,
+                OptionError::InvalidTriggerPriceError
+            );
+        } else {
+            require_gt!(
+                option_detail.strike_price,
+                trigger_price,
+                OptionError::InvalidTriggerPriceError
+            );
+        }
+    }
+
+    option_detail.auto_exercise_trigger_price = params.trigger_price;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetExerciseTriggerParams)]
+pub struct SetExerciseTrigger<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>, // Target price asset
+
+    #[account(
+        mut,
+        seeds = [b"option", owner.key().as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+}
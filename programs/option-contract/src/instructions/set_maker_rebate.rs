@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMakerRebateParams {
+    pub maker_rebate_bps: u16, // share of the protocol fee rebated to the pool
+    pub maker_rebate_budget_usd: u64, // per epoch, scaled by USD_DECIMALS, 0 = no rebates
+    pub maker_rebate_epoch_sec: i64,
+}
+
+pub fn set_maker_rebate<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetMakerRebate<'info>>,
+    params: &SetMakerRebateParams,
+) -> Result<u8> {
+    // validate inputs
+    if params.maker_rebate_bps as u128 > Contract::BPS_POWER || params.maker_rebate_epoch_sec <= 0 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetMakerRebate, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update contract data, a new epoch length starts a fresh budget
+    let contract = ctx.accounts.contract.as_mut();
+    contract.maker_rebate_bps = params.maker_rebate_bps;
+    contract.maker_rebate_budget_usd = params.maker_rebate_budget_usd;
+    if contract.maker_rebate_epoch_sec != params.maker_rebate_epoch_sec {
+        contract.maker_rebate_epoch_sec = params.maker_rebate_epoch_sec;
+        contract.maker_rebate_epoch_start = 0;
+        contract.maker_rebates_in_epoch_usd = 0;
+    }
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetMakerRebateParams)]
+pub struct SetMakerRebate<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+}
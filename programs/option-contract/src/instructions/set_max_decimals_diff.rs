@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMaxDecimalsDiffParams {
+    pub max_decimals_diff: u8,
+    pub pool_name: String,
+}
+
+pub fn set_max_decimals_diff<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetMaxDecimalsDiff<'info>>,
+    params: &SetMaxDecimalsDiffParams,
+) -> Result<u8> {
+    // validate inputs, 10^max_decimals_diff has to stay well within u64 amounts
+    if params.max_decimals_diff > 18 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetMaxDecimalsDiff, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update pool data
+    let pool = ctx.accounts.pool.as_mut();
+    pool.max_decimals_diff = params.max_decimals_diff;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetMaxDecimalsDiffParams)]
+pub struct SetMaxDecimalsDiff<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMaxOpenOptionsParams {
+    pub max_open_options: u64, // valid options a user may hold at once, 0 = unlimited
+}
+
+pub fn set_max_open_options<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetMaxOpenOptions<'info>>,
+    params: &SetMaxOpenOptionsParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetMaxOpenOptions, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update contract data
+    let contract = ctx.accounts.contract.as_mut();
+    contract.max_open_options = params.max_open_options;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetMaxOpenOptionsParams)]
+pub struct SetMaxOpenOptions<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+}
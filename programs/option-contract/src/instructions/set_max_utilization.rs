@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMaxUtilizationParams {
+    pub max_utilization_bps: u16, // max locked share of each custody, 0 = no cap
+    pub pool_name: String,
+}
+
+pub fn set_max_utilization<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetMaxUtilization<'info>>,
+    params: &SetMaxUtilizationParams,
+) -> Result<u8> {
+    // validate inputs
+    if params.max_utilization_bps as u128 > Contract::BPS_POWER {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetMaxUtilization, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update pool data
+    let pool = ctx.accounts.pool.as_mut();
+    pool.max_utilization_bps = params.max_utilization_bps;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetMaxUtilizationParams)]
+pub struct SetMaxUtilization<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+}
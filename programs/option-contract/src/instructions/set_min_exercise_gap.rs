@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMinExerciseGapParams {
+    pub min_exercise_slot_gap: u64, // slots between writing and exercising an option
+}
+
+pub fn set_min_exercise_gap<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetMinExerciseGap<'info>>,
+    params: &SetMinExerciseGapParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetMinExerciseGap, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update contract data
+    let contract = ctx.accounts.contract.as_mut();
+    contract.min_exercise_slot_gap = params.min_exercise_slot_gap;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetMinExerciseGapParams)]
+pub struct SetMinExerciseGap<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMinPremiumParams {
+    pub min_premium_usd: u64, // scaled by USD_DECIMALS, 0 = any non zero premium
+}
+
+pub fn set_min_premium<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetMinPremium<'info>>,
+    params: &SetMinPremiumParams,
+) -> Result<u8> {
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetMinPremium, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update contract data
+    let contract = ctx.accounts.contract.as_mut();
+    contract.min_premium_usd = params.min_premium_usd;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetMinPremiumParams)]
+pub struct SetMinPremium<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+}
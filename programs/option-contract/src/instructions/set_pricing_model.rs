@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, OptionDetail, Pool, PricingModel,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetPricingModelParams {
+    pub pricing_model: PricingModel,
+    pub pricing_tolerance_bps: u16, // max at the money gap of an approximate model to Black-Scholes
+    pub pool_name: String,
+}
+
+pub fn set_pricing_model<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetPricingModel<'info>>,
+    params: &SetPricingModelParams,
+) -> Result<u8> {
+    // validate inputs, an approximate model has to price close to Black-Scholes at the money
+    if params.pricing_model == PricingModel::Approximation
+        && !OptionDetail::is_approximation_within(
+            ctx.accounts.custody.volatility_bps,
+            params.pricing_tolerance_bps,
+        )?
+    {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetPricingModel, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody data
+    let custody = ctx.accounts.custody.as_mut();
+    custody.pricing_model = params.pricing_model;
+    custody.pricing_tolerance_bps = params.pricing_tolerance_bps;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetPricingModelParams)]
+pub struct SetPricingModel<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetProtocolFeeParams {
+    pub fee_bps: u16, // protocol share of the premium
+    pub fee_recipient: Pubkey, // owner of the token accounts fees are paid to
+}
+
+pub fn set_protocol_fee<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetProtocolFee<'info>>,
+    params: &SetProtocolFeeParams,
+) -> Result<u8> {
+    // validate inputs
+    if params.fee_bps as u128 > Contract::BPS_POWER {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetProtocolFee, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update contract data
+    let contract = ctx.accounts.contract.as_mut();
+    contract.fee_bps = params.fee_bps;
+    contract.fee_recipient = params.fee_recipient;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetProtocolFeeParams)]
+pub struct SetProtocolFee<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetRiskGroupParams {
+    pub pool_name: String,
+    pub risk_group: u8, // 0 - remove the custody from its risk group
+    pub max_locked_usd: u64, // shared cap of the group in usd, 0 = no cap
+}
+
+pub fn set_risk_group<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetRiskGroup<'info>>,
+    params: &SetRiskGroupParams,
+) -> Result<u8> {
+    // validate inputs
+    if params.risk_group > Pool::MAX_RISK_GROUPS {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetRiskGroup, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody and pool data
+    let custody = ctx.accounts.custody.as_mut();
+    custody.risk_group = params.risk_group;
+    if params.risk_group > 0 {
+        let pool = ctx.accounts.pool.as_mut();
+        pool.risk_group_caps[(params.risk_group - 1) as usize] = params.max_locked_usd;
+    }
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetRiskGroupParams)]
+pub struct SetRiskGroup<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    // custody tagged into the risk group
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetSettlementCapParams {
+    pub settlement_cap_per_epoch: u64,
+    pub settlement_epoch_sec: i64,
+    pub pool_name: String,
+}
+
+pub fn set_settlement_cap<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetSettlementCap<'info>>,
+    params: &SetSettlementCapParams,
+) -> Result<u8> {
+    // validate inputs, a cap needs an epoch to be measured over
+    if params.settlement_cap_per_epoch > 0 && params.settlement_epoch_sec <= 0 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetSettlementCap, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody data
+    let custody = ctx.accounts.custody.as_mut();
+    custody.settlement_cap_per_epoch = params.settlement_cap_per_epoch;
+    custody.settlement_epoch_sec = params.settlement_epoch_sec;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetSettlementCapParams)]
+pub struct SetSettlementCap<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, OraclePrice, Pool,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetSettlementWindowParams {
+    pub settlement_window_sec: i64,
+    pub pool_name: String,
+}
+
+pub fn set_settlement_window<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetSettlementWindow<'info>>,
+    params: &SetSettlementWindowParams,
+) -> Result<u8> {
+    // validate inputs, reads have to stay within the settlement grace period
+    if params.settlement_window_sec < 0
+        || params.settlement_window_sec > OraclePrice::SETTLEMENT_GRACE_SEC
+    {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetSettlementWindow, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody data
+    let custody = ctx.accounts.custody.as_mut();
+    custody.settlement_window_sec = params.settlement_window_sec;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetSettlementWindowParams)]
+pub struct SetSettlementWindow<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract, Custody, OptionDetail, Pool, PricingModel,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetVolatilityParams {
+    pub volatility_bps: u16, // annualized
+    pub pool_name: String,
+}
+
+pub fn set_volatility<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetVolatility<'info>>,
+    params: &SetVolatilityParams,
+) -> Result<u8> {
+    // validate inputs, Black-Scholes needs a positive volatility
+    if params.volatility_bps == 0 {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+    let custody = &ctx.accounts.custody;
+    if custody.pricing_model == PricingModel::Approximation
+        && !OptionDetail::is_approximation_within(
+            params.volatility_bps,
+            custody.pricing_tolerance_bps,
+        )?
+    {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::SetVolatility, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody data
+    let custody = ctx.accounts.custody.as_mut();
+    custody.volatility_bps = params.volatility_bps;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetVolatilityParams)]
+pub struct SetVolatility<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+}
@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::OptionError,
+    state::{Contract, Custody, OptionBookPage, OptionDetail, Pool, User},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransferOptionParams {
+    pub option_index: u64,
+    pub new_owner: Pubkey,
+    pub pool_name: String,
+}
+
+#[event]
+pub struct OptionTransferred {
+    pub option_index: u64,
+    pub owner: Pubkey,
+    pub new_option_index: u64,
+    pub new_owner: Pubkey,
+}
+
+// Option accounts are seeded by their owner, so a transfer migrates the option: it is copied
+// into a new account under the next option index of the new owner and the old one is closed.
+pub fn transfer_option(ctx: Context<TransferOption>, params: &TransferOptionParams) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    let contract = &ctx.accounts.contract;
+    let pool = &mut ctx.accounts.pool;
+    let user = &mut ctx.accounts.user;
+    let new_user = &mut ctx.accounts.new_user;
+    let old_option_detail = &ctx.accounts.old_option_detail;
+    let option_detail = &mut ctx.accounts.option_detail;
+
+    require_keys_neq!(params.new_owner, owner.key(), OptionError::InvalidOwner);
+
+    // Only live positions change hands, settled ones are claimed or closed by their holder
+    require!(old_option_detail.valid, OptionError::OptionNotValid);
+    require_eq!(
+        old_option_detail.exercised,
+        0,
+        OptionError::OptionAlreadyExercised
+    );
+    require!(
+        !old_option_detail.settlement_frozen,
+        OptionError::SettlementFrozenError
+    );
+
+    let new_option_index = new_user.next_option_index()?;
+    option_detail.set_inner((***old_option_detail).clone());
+    option_detail.owner = params.new_owner;
+    option_detail.index = new_option_index;
+
+    new_user.option_index = new_option_index;
+    new_user.add_open_option(contract.max_open_options)?;
+    user.remove_open_option();
+
+    pool.replace_open_option(
+        &mut ctx.accounts.option_book_page,
+        &old_option_detail.key(),
+        option_detail.key(),
+    );
+
+    emit!(OptionTransferred {
+        option_index: params.option_index,
+        owner: owner.key(),
+        new_option_index,
+        new_owner: params.new_owner,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(params: TransferOptionParams)]
+pub struct TransferOption<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump
+    )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool",
+                 params.pool_name.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody.mint.as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", owner.key().as_ref()],
+        bump,
+    )]
+    pub user: Box<Account<'info, User>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = User::LEN,
+        seeds = [b"user", params.new_owner.as_ref()],
+        bump,
+    )]
+    pub new_user: Box<Account<'info, User>>,
+
+    #[account(
+        mut,
+        seeds = [b"option", owner.key().as_ref(),
+                params.option_index.to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump,
+        has_one = owner @ OptionError::InvalidOwner,
+        close = owner
+    )]
+    pub old_option_detail: Box<Account<'info, OptionDetail>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = OptionDetail::LEN,
+        seeds = [b"option", params.new_owner.as_ref(),
+                // wraps only at u64::MAX, where next_option_index() rejects the instruction
+                new_user.option_index.wrapping_add(1).to_le_bytes().as_ref(),
+                pool.key().as_ref(), custody.key().as_ref()],
+        bump
+    )]
+    pub option_detail: Box<Account<'info, OptionDetail>>,
+
+    #[account(
+        mut,
+        seeds = [b"option_book",
+                 pool.key().as_ref(),
+                 old_option_detail.book_page.to_le_bytes().as_ref()],
+        bump = option_book_page.bump
+    )]
+    pub option_book_page: Box<Account<'info, OptionBookPage>>,
+
+    pub system_program: Program<'info, System>,
+}
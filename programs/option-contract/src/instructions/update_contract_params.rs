@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    multisig::{AdminInstruction, Multisig},
+    Contract,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateContractParamsParams {
+    pub fee_bps: u16, // protocol share of option premiums
+    pub max_price_age_sec: u64, // oracle staleness limit
+    pub max_price_conf_bps: u64, // oracle confidence limit, 0 = unchecked
+    pub fee_recipient: Pubkey, // owner of the token accounts protocol fees are paid to
+    pub min_premium_usd: u64, // dust floor of the premium of a write, scaled by USD_DECIMALS
+    pub max_publish_time_skew_sec: u64, // max publish time gap of oracle feeds used together
+}
+
+pub fn update_contract_params<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateContractParams<'info>>,
+    params: &UpdateContractParamsParams,
+) -> Result<u8> {
+    // validate inputs
+    if params.max_price_age_sec == 0
+        || params.max_price_conf_bps > Contract::BPS_POWER as u64
+        || params.max_publish_time_skew_sec == 0
+        || params.fee_bps as u128 > Contract::BPS_POWER
+    {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::UpdateContractParams, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update contract data
+    let contract = ctx.accounts.contract.as_mut();
+    contract.fee_bps = params.fee_bps;
+    contract.max_price_age_sec = params.max_price_age_sec;
+    contract.max_price_conf_bps = params.max_price_conf_bps;
+    contract.fee_recipient = params.fee_recipient;
+    contract.min_premium_usd = params.min_premium_usd;
+    contract.max_publish_time_skew_sec = params.max_publish_time_skew_sec;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: UpdateContractParamsParams)]
+pub struct UpdateContractParams<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    errors::{OptionError, PoolError},
+    state::{
+        multisig::{AdminInstruction, Multisig},
+        Contract, Custody, Pool,
+    },
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct WithdrawReserveParams {
+    pub amount: u64, // of the custody reserve, in custody tokens
+    pub pool_name: String,
+}
+
+// Pays part of the auto exercise reserve of a custody to the fee recipient with multi sig,
+// the reserve is not LP funds and is left out of the AUM until then
+pub fn withdraw_reserve<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawReserve<'info>>,
+    params: &WithdrawReserveParams,
+) -> Result<u8> {
+    // validate inputs
+    if params.amount == 0 || params.amount > ctx.accounts.custody.token_reserved {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    // validate signatures
+    let mut multisig = ctx.accounts.multisig.load_mut()?;
+    let signatures_left = multisig.sign_multisig(
+        &ctx.accounts.signer,
+        &Multisig::get_account_infos(&ctx)[1..],
+        &Multisig::get_instruction_data(AdminInstruction::WithdrawReserve, params)?,
+    )?;
+
+    if signatures_left > 0 {
+        msg!(
+            "Instruction has been signed but more signatures are required: {}",
+            signatures_left
+        );
+        return Ok(signatures_left);
+    }
+
+    // update custody data before the tokens leave it
+    let custody = ctx.accounts.custody.as_mut();
+    custody.take_reserve(params.amount)?;
+
+    ctx.accounts.contract.transfer_tokens(
+        ctx.accounts.custody_token_account.to_account_info(),
+        ctx.accounts.receiving_account.to_account_info(),
+        &ctx.accounts.custody_mint,
+        ctx.accounts.transfer_authority.to_account_info(),
+        ctx.accounts.custody_token_program.to_account_info(),
+        params.amount,
+    )?;
+
+    Ok(0)
+}
+
+#[derive(Accounts)]
+#[instruction(params: WithdrawReserveParams)]
+pub struct WithdrawReserve<'info> {
+    #[account()]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig"],
+        bump = multisig.load()?.bump
+    )]
+    pub multisig: AccountLoader<'info, Multisig>,
+
+    /// CHECK: empty PDA, authority for token accounts
+    #[account(
+        seeds = [b"transfer_authority"],
+        bump = contract.transfer_authority_bump
+    )]
+    pub transfer_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"contract"],
+        bump = contract.bump,
+      )]
+    pub contract: Box<Account<'info, Contract>>,
+
+    #[account(
+        seeds = [b"pool", params.pool_name.as_bytes()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody",
+                 pool.key().as_ref(),
+                 custody_mint.key().as_ref()],
+        bump = custody.bump
+    )]
+    pub custody: Box<Account<'info, Custody>>,
+
+    #[account(
+        mut,
+        seeds = [b"custody_token_account",
+                 pool.key().as_ref(),
+                 custody_mint.key().as_ref()],
+        bump = custody.token_account_bump
+    )]
+    pub custody_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = receiving_account.mint == custody.mint @ PoolError::InvalidCustodyTokenError,
+        constraint = receiving_account.owner == contract.fee_recipient @ OptionError::InvalidFeeAccountError
+    )]
+    pub receiving_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub custody_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub custody_token_program: Interface<'info, TokenInterface>,
+}
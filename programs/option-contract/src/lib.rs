@@ -14,8 +14,8 @@ declare_id!("GSmqNhxAhrLJjcxd9G2ts3obF9va9QBRezm6PMQJuE9b");
 pub mod option_contract {
     use super::*;
     // Initialize smart contract Accounts
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::initialize::initialize(ctx)
+    pub fn initialize(ctx: Context<Initialize>, params: InitializeParams) -> Result<()> {
+        instructions::initialize::initialize(ctx, &params)
     }
 
     // Add admins as multisig signers
@@ -67,6 +67,366 @@ pub mod option_contract {
         instructions::remove_custody::remove_custody(ctx, &params)
     }
 
+    // Set premium multiplier of custody with multi sig
+    pub fn set_premium_multiplier<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPremiumMultiplier<'info>>,
+        params: SetPremiumMultiplierParams,
+    ) -> Result<u8> {
+        instructions::set_premium_multiplier::set_premium_multiplier(ctx, &params)
+    }
+
+    // Set premium cap of custody with multi sig
+    pub fn set_premium_cap<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPremiumCap<'info>>,
+        params: SetPremiumCapParams,
+    ) -> Result<u8> {
+        instructions::set_premium_cap::set_premium_cap(ctx, &params)
+    }
+
+    // Acknowledge custody rebalance and reset collected premium with multi sig
+    pub fn rebalance_ack<'info>(
+        ctx: Context<'_, '_, '_, 'info, RebalanceAck<'info>>,
+        params: RebalanceAckParams,
+    ) -> Result<u8> {
+        instructions::rebalance_ack::rebalance_ack(ctx, &params)
+    }
+
+    // Set collateral buffer of custody with multi sig
+    pub fn set_collateral_buffer<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetCollateralBuffer<'info>>,
+        params: SetCollateralBufferParams,
+    ) -> Result<u8> {
+        instructions::set_collateral_buffer::set_collateral_buffer(ctx, &params)
+    }
+
+    // Set write cooldown after oracle moves of custody with multi sig
+    pub fn set_write_cooldown<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetWriteCooldown<'info>>,
+        params: SetWriteCooldownParams,
+    ) -> Result<u8> {
+        instructions::set_write_cooldown::set_write_cooldown(ctx, &params)
+    }
+
+    // Record oracle moves of custody by keeper
+    pub fn monitor_price_move(
+        ctx: Context<MonitorPriceMove>,
+        params: MonitorPriceMoveParams,
+    ) -> Result<()> {
+        instructions::monitor_price_move::monitor_price_move(ctx, &params)
+    }
+
+    // Freeze settlement of a disputed option with multi sig
+    pub fn freeze_option<'info>(
+        ctx: Context<'_, '_, '_, 'info, FreezeOption<'info>>,
+        params: FreezeOptionParams,
+    ) -> Result<u8> {
+        instructions::freeze_option::freeze_option(ctx, &params)
+    }
+
+    // Unfreeze settlement of an option with multi sig
+    pub fn unfreeze_option<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnfreezeOption<'info>>,
+        params: UnfreezeOptionParams,
+    ) -> Result<u8> {
+        instructions::unfreeze_option::unfreeze_option(ctx, &params)
+    }
+
+    // Set lot size of locked amounts for custody with multi sig
+    pub fn set_lot_size<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetLotSize<'info>>,
+        params: SetLotSizeParams,
+    ) -> Result<u8> {
+        instructions::set_lot_size::set_lot_size(ctx, &params)
+    }
+
+    // Set premium currencies accepted by pool with multi sig
+    pub fn set_allowed_premium_mints<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAllowedPremiumMints<'info>>,
+        params: SetAllowedPremiumMintsParams,
+    ) -> Result<u8> {
+        instructions::set_allowed_premium_mints::set_allowed_premium_mints(ctx, &params)
+    }
+
+    // Set claim window of auto exercised profit for custody with multi sig
+    pub fn set_claim_window<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetClaimWindow<'info>>,
+        params: SetClaimWindowParams,
+    ) -> Result<u8> {
+        instructions::set_claim_window::set_claim_window(ctx, &params)
+    }
+
+    // View recent settlement prices of custody
+    pub fn get_settlement_prices(
+        ctx: Context<GetSettlementPrices>,
+        params: GetSettlementPricesParams,
+    ) -> Result<SettlementPrices> {
+        instructions::get_settlement_prices::get_settlement_prices(ctx, &params)
+    }
+
+    // Set min premium to locked collateral ratio for custody with multi sig
+    pub fn set_min_premium_ratio<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMinPremiumRatio<'info>>,
+        params: SetMinPremiumRatioParams,
+    ) -> Result<u8> {
+        instructions::set_min_premium_ratio::set_min_premium_ratio(ctx, &params)
+    }
+
+    // Exempt user from settlement fees with multi sig
+    pub fn add_fee_exempt<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddFeeExempt<'info>>,
+        params: AddFeeExemptParams,
+    ) -> Result<u8> {
+        instructions::add_fee_exempt::add_fee_exempt(ctx, &params)
+    }
+
+    // Remove settlement fee exemption of user with multi sig
+    pub fn remove_fee_exempt<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveFeeExempt<'info>>,
+        params: RemoveFeeExemptParams,
+    ) -> Result<u8> {
+        instructions::remove_fee_exempt::remove_fee_exempt(ctx, &params)
+    }
+
+    // Set backup oracles of custody for median pricing with multi sig
+    pub fn set_backup_oracles<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetBackupOracles<'info>>,
+        params: SetBackupOraclesParams,
+    ) -> Result<u8> {
+        instructions::set_backup_oracles::set_backup_oracles(ctx, &params)
+    }
+
+    // Set settlement payout cap per epoch for custody with multi sig
+    pub fn set_settlement_cap<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetSettlementCap<'info>>,
+        params: SetSettlementCapParams,
+    ) -> Result<u8> {
+        instructions::set_settlement_cap::set_settlement_cap(ctx, &params)
+    }
+
+    // Stamp current fair value on option, callable by anyone
+    pub fn mark_option(
+        ctx: Context<MarkOption>,
+        params: MarkOptionParams,
+    ) -> Result<()> {
+        instructions::mark_option::mark_option(ctx, &params)
+    }
+
+    // Set early exercise penalty for custody with multi sig
+    pub fn set_early_exercise_penalty<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetEarlyExercisePenalty<'info>>,
+        params: SetEarlyExercisePenaltyParams,
+    ) -> Result<u8> {
+        instructions::set_early_exercise_penalty::set_early_exercise_penalty(ctx, &params)
+    }
+
+    // Set quote custody securing puts of pool with multi sig
+    pub fn set_quote_custody<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetQuoteCustody<'info>>,
+        params: SetQuoteCustodyParams,
+    ) -> Result<u8> {
+        instructions::set_quote_custody::set_quote_custody(ctx, &params)
+    }
+
+    // View net delta of pool on a custody for hedging
+    pub fn get_pool_delta<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetPoolDelta<'info>>,
+        params: GetPoolDeltaParams,
+    ) -> Result<PoolDelta> {
+        instructions::get_pool_delta::get_pool_delta(ctx, &params)
+    }
+
+    // Set lamports program accounts keep above rent-exemption
+    pub fn set_rent_buffer<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetRentBuffer<'info>>,
+        params: SetRentBufferParams,
+    ) -> Result<u8> {
+        instructions::set_rent_buffer::set_rent_buffer(ctx, &params)
+    }
+
+    // Set take-profit price at which keepers may exercise the option
+    pub fn set_exercise_trigger(
+        ctx: Context<SetExerciseTrigger>,
+        params: SetExerciseTriggerParams,
+    ) -> Result<()> {
+        instructions::set_exercise_trigger::set_exercise_trigger(ctx, &params)
+    }
+
+    // Set max decimals gap between tokens of the pool
+    pub fn set_max_decimals_diff<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMaxDecimalsDiff<'info>>,
+        params: SetMaxDecimalsDiffParams,
+    ) -> Result<u8> {
+        instructions::set_max_decimals_diff::set_max_decimals_diff(ctx, &params)
+    }
+
+    // View implied volatility of a market premium
+    pub fn get_implied_volatility(
+        ctx: Context<GetImpliedVolatility>,
+        params: GetImpliedVolatilityParams,
+    ) -> Result<ImpliedVolatility> {
+        instructions::get_implied_volatility::get_implied_volatility(ctx, &params)
+    }
+
+    // Reset user option index to the highest existing option
+    pub fn repair_user_index<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RepairUserIndex<'info>>,
+        params: RepairUserIndexParams,
+    ) -> Result<u8> {
+        instructions::repair_user_index::repair_user_index(ctx, &params)
+    }
+
+    // Set Black-Scholes volatility of a custody
+    pub fn set_volatility<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetVolatility<'info>>,
+        params: SetVolatilityParams,
+    ) -> Result<u8> {
+        instructions::set_volatility::set_volatility(ctx, &params)
+    }
+
+    // Record an oracle read around expiry for averaged settlement
+    pub fn record_settlement_read(
+        ctx: Context<RecordSettlementRead>,
+        params: RecordSettlementReadParams,
+    ) -> Result<()> {
+        instructions::record_settlement_read::record_settlement_read(ctx, &params)
+    }
+
+    // Set window around expiry of averaged settlement
+    pub fn set_settlement_window<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetSettlementWindow<'info>>,
+        params: SetSettlementWindowParams,
+    ) -> Result<u8> {
+        instructions::set_settlement_window::set_settlement_window(ctx, &params)
+    }
+
+    // Allow a keeper to auto exercise options of the pool
+    pub fn add_keeper<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddKeeper<'info>>,
+        params: AddKeeperParams,
+    ) -> Result<u8> {
+        instructions::add_keeper::add_keeper(ctx, &params)
+    }
+
+    // Remove a keeper from the allowlist of the pool
+    pub fn remove_keeper<'info>(
+        ctx: Context<'_, '_, '_, 'info, RemoveKeeper<'info>>,
+        params: RemoveKeeperParams,
+    ) -> Result<u8> {
+        instructions::remove_keeper::remove_keeper(ctx, &params)
+    }
+
+    // Tag a custody into a risk group of correlated assets and set the shared cap of the group
+    pub fn set_risk_group<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetRiskGroup<'info>>,
+        params: SetRiskGroupParams,
+    ) -> Result<u8> {
+        instructions::set_risk_group::set_risk_group(ctx, &params)
+    }
+
+    // Set the protocol share of option premiums and who receives it
+    pub fn set_protocol_fee<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetProtocolFee<'info>>,
+        params: SetProtocolFeeParams,
+    ) -> Result<u8> {
+        instructions::set_protocol_fee::set_protocol_fee(ctx, &params)
+    }
+
+    // Cap the deposits of each liquidity provider and the total deposits of the pool
+    pub fn set_deposit_cap<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetDepositCap<'info>>,
+        params: SetDepositCapParams,
+    ) -> Result<u8> {
+        instructions::set_deposit_cap::set_deposit_cap(ctx, &params)
+    }
+
+    // Set slots an option must age before it can be exercised
+    pub fn set_min_exercise_gap<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMinExerciseGap<'info>>,
+        params: SetMinExerciseGapParams,
+    ) -> Result<u8> {
+        instructions::set_min_exercise_gap::set_min_exercise_gap(ctx, &params)
+    }
+
+    // Set share of auto exercised profit kept in the reserve of the locked custody
+    pub fn set_auto_exercise_reserve<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAutoExerciseReserve<'info>>,
+        params: SetAutoExerciseReserveParams,
+    ) -> Result<u8> {
+        instructions::set_auto_exercise_reserve::set_auto_exercise_reserve(ctx, &params)
+    }
+
+    // Set underlying units per contract options of custody can be written in with multi sig
+    pub fn set_contract_size<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetContractSize<'info>>,
+        params: SetContractSizeParams,
+    ) -> Result<u8> {
+        instructions::set_contract_size::set_contract_size(ctx, &params)
+    }
+
+    // Pause or resume option sales and buybacks in an emergency with multi sig
+    pub fn set_paused<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPaused<'info>>,
+        params: SetPausedParams,
+    ) -> Result<u8> {
+        instructions::set_paused::set_paused(ctx, &params)
+    }
+
+    // Set how many valid options a user may hold at once with multi sig
+    pub fn set_max_open_options<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMaxOpenOptions<'info>>,
+        params: SetMaxOpenOptionsParams,
+    ) -> Result<u8> {
+        instructions::set_max_open_options::set_max_open_options(ctx, &params)
+    }
+
+    // Set max locked share of each custody of the pool with multi sig
+    pub fn set_max_utilization<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMaxUtilization<'info>>,
+        params: SetMaxUtilizationParams,
+    ) -> Result<u8> {
+        instructions::set_max_utilization::set_max_utilization(ctx, &params)
+    }
+
+    // Set the minimum premium in usd an option may be written for
+    pub fn set_min_premium<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMinPremium<'info>>,
+        params: SetMinPremiumParams,
+    ) -> Result<u8> {
+        instructions::set_min_premium::set_min_premium(ctx, &params)
+    }
+
+    // Update the fee, fee recipient, oracle limits and premium floor of the contract at once
+    pub fn update_contract_params<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateContractParams<'info>>,
+        params: UpdateContractParamsParams,
+    ) -> Result<u8> {
+        instructions::update_contract_params::update_contract_params(ctx, &params)
+    }
+
+    // Set the pricing model of the premiums of a custody
+    pub fn set_pricing_model<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPricingModel<'info>>,
+        params: SetPricingModelParams,
+    ) -> Result<u8> {
+        instructions::set_pricing_model::set_pricing_model(ctx, &params)
+    }
+
+    // Set the maker rebate paid to the pool out of protocol fees with multi sig
+    pub fn set_maker_rebate<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMakerRebate<'info>>,
+        params: SetMakerRebateParams,
+    ) -> Result<u8> {
+        instructions::set_maker_rebate::set_maker_rebate(ctx, &params)
+    }
+
+    // Pay out the auto exercise reserve of a custody to the fee recipient with multi sig
+    pub fn withdraw_reserve<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawReserve<'info>>,
+        params: WithdrawReserveParams,
+    ) -> Result<u8> {
+        instructions::withdraw_reserve::withdraw_reserve(ctx, &params)
+    }
+
     // Add liquidity 
     pub fn add_liquidity<'info>(
         ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
@@ -82,36 +442,56 @@ pub mod option_contract {
         instructions::remove_liquidity::remove_liquidity(ctx, &params)
     }
 
-    pub fn open_limit_option(ctx: Context<OpenLimitOption>, params: OpenLimitOptionParams) -> Result<()> {
+    pub fn open_limit_option<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OpenLimitOption<'info>>,
+        params: OpenLimitOptionParams,
+    ) -> Result<()> {
         instructions::open_limit_option::open_limit_option(ctx, &params)
     }
 
     // Buy option from user to liquidity pool before expired time by user
-    pub fn close_limit_option(ctx: Context<CloseLimitOption>, params: CloseLimitOptionParams) -> Result<()> {
+    pub fn close_limit_option<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseLimitOption<'info>>,
+        params: CloseLimitOptionParams,
+    ) -> Result<()> {
         instructions::close_limit_option::close_limit_option(ctx, &params)
     }
 
     // Sell option froom liquidity to user
-    pub fn open_option(ctx: Context<OpenOption>, params: OpenOptionParams) -> Result<()> {
+    pub fn open_option<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OpenOption<'info>>,
+        params: OpenOptionParams,
+    ) -> Result<u64> {
         instructions::open_option::open_option(ctx, &params)
     }
 
     // Buy option from user to liquidity pool before expired time by user
-    pub fn close_option(ctx: Context<CloseOption>, params: CloseOptionParams) -> Result<()> {
+    pub fn close_option<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseOption<'info>>,
+        params: CloseOptionParams,
+    ) -> Result<()> {
         instructions::close_option::close_option(ctx, &params)
     }
 
     // Exercise option before expired time by user
-    pub fn exercise_option(
-        ctx: Context<ExerciseOption>,
+    pub fn exercise_option<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExerciseOption<'info>>,
         params: ExerciseOptionParams,
     ) -> Result<()> {
         instructions::exercise_option::exercise_option(ctx, &params)
     }
 
+    // Allow a delegate to exercise options on behalf of user
+    pub fn set_exercise_delegate(
+        ctx: Context<SetExerciseDelegate>,
+        params: SetExerciseDelegateParams,
+    ) -> Result<()> {
+        instructions::set_exercise_delegate::set_exercise_delegate(ctx, &params)
+    }
+
     // Exercise option after expired time by bot
-    pub fn auto_exercise(
-        ctx: Context<AutoExerciseOption>,
+    pub fn auto_exercise<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AutoExerciseOption<'info>>,
         params: AutoExerciseOptionParams,
     ) -> Result<()> {
         instructions::auto_exercise::auto_exercise(ctx, &params)
@@ -121,4 +501,100 @@ pub mod option_contract {
     pub fn claim_option(ctx: Context<ClaimOption>, params: ClaimOptionParams) -> Result<()> {
         instructions::claim_option::claim_option(ctx, &params)
     }
+
+    // Roll collateral of worthless expired option into a new option by user
+    pub fn roll_expired<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RollExpired<'info>>,
+        params: RollExpiredParams,
+    ) -> Result<()> {
+        instructions::roll_expired::roll_expired(ctx, &params)
+    }
+
+    // View locked collateral of pool by option type and strike
+    pub fn get_locked_collateral<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetLockedCollateral<'info>>,
+        params: GetLockedCollateralParams,
+    ) -> Result<LockedCollateral> {
+        instructions::get_locked_collateral::get_locked_collateral(ctx, &params)
+    }
+
+    // View profit of option exercise capped by available pool liquidity
+    pub fn get_exercisable_profit(
+        ctx: Context<GetExercisableProfit>,
+        params: GetExercisableProfitParams,
+    ) -> Result<ExercisableProfit> {
+        instructions::get_exercisable_profit::get_exercisable_profit(ctx, &params)
+    }
+
+    // View realized pnl of pool: premiums collected minus exercise payouts
+    pub fn get_realized_pnl(
+        ctx: Context<GetRealizedPnl>,
+        params: GetRealizedPnlParams,
+    ) -> Result<RealizedPnl> {
+        instructions::get_realized_pnl::get_realized_pnl(ctx, &params)
+    }
+
+    // View current fair value of user options on a custody
+    pub fn get_user_portfolio_value<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetUserPortfolioValue<'info>>,
+        params: GetUserPortfolioValueParams,
+    ) -> Result<PortfolioValue> {
+        instructions::get_user_portfolio_value::get_user_portfolio_value(ctx, &params)
+    }
+
+    // Close settled options of the caller and refund their rent
+    pub fn close_options_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseOptionsBatch<'info>>,
+        params: CloseOptionsBatchParams,
+    ) -> Result<()> {
+        instructions::close_options_batch::close_options_batch(ctx, &params)
+    }
+
+    // View a page of the open options of a pool
+    pub fn get_open_options<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetOpenOptions<'info>>,
+        params: GetOpenOptionsParams,
+    ) -> Result<OpenOptionsPage> {
+        instructions::get_open_options::get_open_options(ctx, &params)
+    }
+
+    // View what exercising the whole option would pay out now
+    pub fn get_option_value<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetOptionValue<'info>>,
+        params: GetOptionValueParams,
+    ) -> Result<u64> {
+        instructions::get_option_value::get_option_value(ctx, &params)
+    }
+
+    // View the room left under the total deposit cap of a pool
+    pub fn get_deposit_capacity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetDepositCapacity<'info>>,
+        params: GetDepositCapacityParams,
+    ) -> Result<DepositCapacity> {
+        instructions::get_deposit_capacity::get_deposit_capacity(ctx, &params)
+    }
+
+    // Settle several expired options of a pool in one transaction
+    pub fn auto_exercise_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AutoExerciseBatch<'info>>,
+        params: AutoExerciseBatchParams,
+    ) -> Result<()> {
+        instructions::auto_exercise_batch::auto_exercise_batch(ctx, &params)
+    }
+
+    // Close a settled option of the caller and refund its rent
+    pub fn close_option_detail(
+        ctx: Context<CloseOptionDetail>,
+        params: CloseOptionDetailParams,
+    ) -> Result<()> {
+        instructions::close_option_detail::close_option_detail(ctx, &params)
+    }
+
+    // Hand a live option of the caller over to a new owner
+    pub fn transfer_option(
+        ctx: Context<TransferOption>,
+        params: TransferOptionParams,
+    ) -> Result<()> {
+        instructions::transfer_option::transfer_option(ctx, &params)
+    }
 }
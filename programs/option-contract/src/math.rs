@@ -69,6 +69,19 @@ where
     }
 }
 
+pub fn checked_as_i128<T>(arg: T) -> Result<i128>
+where
+    T: Display + num_traits::ToPrimitive + Clone,
+{
+    let option: Option<i128> = num_traits::NumCast::from(arg.clone());
+    if let Some(res) = option {
+        Ok(res)
+    } else {
+        msg!("Error: Overflow in {} as i128", arg);
+        err!(MathError::OverflowMathError)
+    }
+}
+
 pub fn checked_mul<T>(arg1: T, arg2: T) -> Result<T>
 where
     T: num_traits::PrimInt + Display,
@@ -1,12 +1,33 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Burn, Transfer, MintTo};
+use crate::{errors::ContractError, math, state::OraclePrice};
+use anchor_spl::{
+    token::{Burn, MintTo},
+    token_interface::{Mint, SyncNative, TransferChecked},
+};
 
 #[account]
 #[derive(Default, Debug)]
 pub struct Contract {
     pub pools: Vec<Pubkey>,
     pub bump: u8,
-    pub transfer_authority_bump:u8
+    pub transfer_authority_bump:u8,
+    pub rent_buffer: u64, // lamports program owned accounts keep above their rent-exempt minimum
+    pub max_price_age_sec: u64, // oracle prints older than this are rejected
+    pub max_price_conf_bps: u64, // max oracle confidence interval relative to the price, 0 = unchecked
+    pub fee_bps: u16, // protocol share of option premiums
+    pub fee_recipient: Pubkey, // owner of the token accounts protocol fees are paid to
+    pub cumulative_protocol_fee_usd: u128, // protocol fees collected on premiums
+    pub min_exercise_slot_gap: u64, // slots an option must age before it can be exercised
+    pub auto_exercise_reserve_bps: u64, // share of auto exercised profit kept in the reserve
+    pub max_open_options: u64, // valid options a user may hold at once, 0 = unlimited
+    pub paused: bool, // emergency stop of option sales and buybacks, exercises and withdrawals stay open
+    pub min_premium_usd: u64, // dust floor of the premium of a write, scaled by USD_DECIMALS
+    pub max_publish_time_skew_sec: u64, // max publish time gap of oracle feeds used together
+    pub maker_rebate_bps: u16, // share of the protocol fee of a write rebated to the pool as writer
+    pub maker_rebate_budget_usd: u64, // rebates paid per rebate epoch, 0 = no rebates
+    pub maker_rebate_epoch_sec: i64,
+    pub maker_rebate_epoch_start: i64,
+    pub maker_rebates_in_epoch_usd: u64,
 }
 
 impl anchor_lang::Id for Contract {
@@ -19,6 +40,7 @@ impl Contract {
     pub const LEN: usize = 8 + std::mem::size_of::<Contract>();
     pub const BPS_DECIMALS: u8 = 4;
     pub const BPS_POWER: u128 = 10u64.pow(Self::BPS_DECIMALS as u32) as u128;
+    pub const MAX_AUTO_EXERCISE_RESERVE_BPS: u64 = 1000;
     pub const USD_DECIMALS:u8 = 6;
     pub const PRICE_DECIMALS:u8 =6;
     pub const LP_DECIMALS:u8 = 6;
@@ -27,6 +49,96 @@ impl Contract {
     }
 
 
+    /// Program owned accounts must either be fully drained (closed) or keep at least their
+    /// rent-exempt minimum plus `rent_buffer` lamports, so they can't be purged by the runtime.
+    pub fn check_rent_exempt(&self, account_info: &AccountInfo) -> Result<()> {
+        let lamports = account_info.try_lamports()?;
+        if lamports == 0 {
+            return Ok(());
+        }
+        let minimum_balance = Rent::get()?
+            .minimum_balance(account_info.data_len())
+            .checked_add(self.rent_buffer)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if lamports < minimum_balance {
+            msg!(
+                "Error: {} would keep {} lamports, below the rent-exempt minimum {}",
+                account_info.key(),
+                lamports,
+                minimum_balance
+            );
+            return err!(ContractError::RentExemptionViolated);
+        }
+        Ok(())
+    }
+
+    /// Protocol share of a premium of `amount`, rounded down so the LP share never goes negative
+    pub fn get_protocol_fee(&self, amount: u64) -> Result<u64> {
+        math::checked_as_u64(math::checked_div(
+            math::checked_mul(amount as u128, self.fee_bps as u128)?,
+            Self::BPS_POWER,
+        )?)
+    }
+
+    /// Share of `protocol_fee` (worth `protocol_fee_usd`) rebated to the pool, which writes the
+    /// option. Rebates are paid while they fit the budget of the current epoch, once it is used
+    /// up the whole fee goes to the protocol again.
+    pub fn take_maker_rebate(
+        &mut self,
+        protocol_fee: u64,
+        protocol_fee_usd: u64,
+        curtime: i64,
+    ) -> Result<u64> {
+        if self.maker_rebate_bps == 0 || self.maker_rebate_budget_usd == 0 {
+            return Ok(0);
+        }
+        let rebate = math::checked_as_u64(math::checked_div(
+            math::checked_mul(protocol_fee as u128, self.maker_rebate_bps as u128)?,
+            Self::BPS_POWER,
+        )?)?;
+        let rebate_usd = math::checked_as_u64(math::checked_div(
+            math::checked_mul(protocol_fee_usd as u128, self.maker_rebate_bps as u128)?,
+            Self::BPS_POWER,
+        )?)?;
+
+        let epoch_start = math::checked_sub(curtime, curtime % self.maker_rebate_epoch_sec)?;
+        if epoch_start != self.maker_rebate_epoch_start {
+            self.maker_rebate_epoch_start = epoch_start;
+            self.maker_rebates_in_epoch_usd = 0;
+        }
+
+        let rebates_in_epoch_usd = math::checked_add(self.maker_rebates_in_epoch_usd, rebate_usd)?;
+        if rebates_in_epoch_usd > self.maker_rebate_budget_usd {
+            msg!(
+                "Maker rebate budget used up: {} paid in epoch",
+                self.maker_rebates_in_epoch_usd
+            );
+            return Ok(0);
+        }
+        self.maker_rebates_in_epoch_usd = rebates_in_epoch_usd;
+        Ok(rebate)
+    }
+
+    pub fn record_protocol_fee(&mut self, amount_usd: u64) -> Result<()> {
+        self.cumulative_protocol_fee_usd =
+            math::checked_add(self.cumulative_protocol_fee_usd, amount_usd as u128)?;
+        Ok(())
+    }
+
+    /// Oracle price checked against the staleness and confidence limits of the contract.
+    pub fn get_oracle_price(
+        &self,
+        oracle_account: &AccountInfo,
+        current_time: i64,
+    ) -> Result<OraclePrice> {
+        OraclePrice::new_from_oracle_checked(
+            oracle_account,
+            current_time,
+            self.max_price_age_sec,
+            self.max_price_conf_bps,
+        )
+    }
+
     pub fn close_token_account<'info>(
         receiver: AccountInfo<'info>,
         token_account: AccountInfo<'info>,
@@ -34,14 +146,29 @@ impl Contract {
         authority: AccountInfo<'info>,
         seeds: &[&[&[u8]]],
     ) -> Result<()> {
-        let cpi_accounts = anchor_spl::token::CloseAccount {
+        let cpi_accounts = anchor_spl::token_interface::CloseAccount {
             account: token_account,
             destination: receiver,
             authority,
         };
         let cpi_context = anchor_lang::context::CpiContext::new(token_program, cpi_accounts);
 
-        anchor_spl::token::close_account(cpi_context.with_signer(seeds))
+        anchor_spl::token_interface::close_account(cpi_context.with_signer(seeds))
+    }
+
+    /// Wraps `amount` lamports of `owner` into its native mint token account. The lamports land
+    /// on top of the rent-exempt reserve the account already holds, so only they are synced.
+    pub fn wrap_sol<'info>(
+        owner: AccountInfo<'info>,
+        token_account: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        Contract::transfer_sol(owner, token_account.clone(), system_program, amount)?;
+
+        let cpi_context = CpiContext::new(token_program, SyncNative { account: token_account });
+        anchor_spl::token_interface::sync_native(cpi_context)
     }
 
     pub fn get_time(&self) -> Result<i64> {
@@ -53,10 +180,17 @@ impl Contract {
         }
     }
 
+    pub fn get_slot(&self) -> Result<u64> {
+        Ok(Clock::get()?.slot)
+    }
+
+    // token transfers go through transfer_checked so both the classic token program
+    // and Token-2022 mints can be used as custody or premium assets
     pub fn transfer_tokens<'info>(
         &self,
         from: AccountInfo<'info>,
         to: AccountInfo<'info>,
+        mint: &InterfaceAccount<'info, Mint>,
         authority: AccountInfo<'info>,
         token_program: AccountInfo<'info>,
         amount: u64,
@@ -66,34 +200,37 @@ impl Contract {
 
         let context = CpiContext::new(
             token_program,
-            Transfer {
+            TransferChecked {
                 from,
+                mint: mint.to_account_info(),
                 to,
                 authority,
             },
         )
         .with_signer(authority_seeds);
 
-        anchor_spl::token::transfer(context, amount)
+        anchor_spl::token_interface::transfer_checked(context, amount, mint.decimals)
     }
 
     pub fn transfer_tokens_from_user<'info>(
         &self,
         from: AccountInfo<'info>,
         to: AccountInfo<'info>,
+        mint: &InterfaceAccount<'info, Mint>,
         authority: AccountInfo<'info>,
         token_program: AccountInfo<'info>,
         amount: u64,
     ) -> Result<()> {
         let context = CpiContext::new(
             token_program,
-            Transfer {
+            TransferChecked {
                 from,
+                mint: mint.to_account_info(),
                 to,
                 authority,
             },
         );
-        anchor_spl::token::transfer(context, amount)
+        anchor_spl::token_interface::transfer_checked(context, amount, mint.decimals)
     }
 
     pub fn mint_tokens<'info>(
@@ -141,6 +278,7 @@ impl Contract {
     }
 
     pub fn transfer_sol_from_owned<'a>(
+        &self,
         program_owned_source_account: AccountInfo<'a>,
         destination_account: AccountInfo<'a>,
         amount: u64,
@@ -155,7 +293,7 @@ impl Contract {
             .checked_sub(amount)
             .ok_or(ProgramError::InsufficientFunds)?;
 
-        Ok(())
+        self.check_rent_exempt(&program_owned_source_account)
     }
 
     pub fn transfer_sol<'a>(
@@ -195,3 +333,56 @@ impl Contract {
             .map_err(|_| ProgramError::InvalidRealloc.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maker_rebates_are_paid_until_the_epoch_budget_runs_out() {
+        // half of the protocol fee is rebated, 15 usd per day
+        let mut contract = Contract {
+            maker_rebate_bps: 5_000,
+            maker_rebate_budget_usd: 15_000_000,
+            maker_rebate_epoch_sec: 86_400,
+            ..Default::default()
+        };
+        let day = 1_700_006_400; // start of an epoch
+        let fee_usd = 20_000_000;
+
+        assert_eq!(
+            contract.take_maker_rebate(2_000, fee_usd, day).unwrap(),
+            1_000
+        );
+        assert_eq!(
+            contract
+                .take_maker_rebate(2_000, fee_usd, day + 60)
+                .unwrap(),
+            0
+        );
+        assert_eq!(contract.maker_rebates_in_epoch_usd, 10_000_000);
+
+        // a smaller rebate that still fits is paid, the budget resets with the next epoch
+        assert_eq!(
+            contract
+                .take_maker_rebate(600, 6_000_000, day + 120)
+                .unwrap(),
+            300
+        );
+        assert_eq!(
+            contract
+                .take_maker_rebate(2_000, fee_usd, day + 86_400)
+                .unwrap(),
+            1_000
+        );
+        assert_eq!(contract.maker_rebates_in_epoch_usd, 10_000_000);
+
+        contract.maker_rebate_budget_usd = 0;
+        assert_eq!(
+            contract
+                .take_maker_rebate(2_000, fee_usd, day + 86_400)
+                .unwrap(),
+            0
+        );
+    }
+}
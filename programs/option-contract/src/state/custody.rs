@@ -1,6 +1,11 @@
 use anchor_lang::prelude::*;
 
-use crate::math;
+use crate::{
+    errors::{ContractError, OptionError},
+    math,
+};
+
+use super::{Contract, OracleKind, OraclePrice, PricingModel};
 
 #[derive(Copy, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
 pub struct Fees {
@@ -10,6 +15,12 @@ pub struct Fees {
     pub remove_liquidity: u64,
 }
 
+#[derive(Copy, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
+pub struct SettlementSnapshot {
+    pub timestamp: i64,
+    pub price: OraclePrice,
+}
+
 #[account]
 #[derive(Default, Debug)]
 pub struct Custody {
@@ -19,9 +30,41 @@ pub struct Custody {
     pub token_account: Pubkey,
     pub decimals: u8,
     pub oracle: Pubkey,
+    pub oracle_kind: OracleKind, // program the oracle and backup oracles are read from
+    pub backup_oracles: [Pubkey; 2], // extra feeds of the asset for median pricing, default = unused
+    pub min_oracle_feeds: u8, // fresh feeds required for a median price, 0 = primary oracle only
+    pub strike_exponent: i32, // exponent strike prices of this custody are scaled by
+    pub volatility_bps: u16, // annualized Black-Scholes volatility of the asset
+    pub pricing_model: PricingModel, // premiums of writes and buybacks
+    pub pricing_tolerance_bps: u16, // max at the money gap of an approximate model to Black-Scholes
+    pub risk_group: u8, // custodies of correlated assets sharing a pool risk cap, 0 = none
     pub token_owned : u64,
     pub token_locked : u64,
+    pub token_claimable: u64, // settled profits owed to holders until claimed or forfeited
+    pub token_reserved: u64, // auto exercise fees, out of the AUM until withdraw_reserve pays them out
     pub fees: Fees, // Maintaining token ratio constant
+    pub premium_multiplier_bps: u64, // scales the Black-Scholes premium, BPS_POWER = 1x
+    pub premium_cap_bps: u64, // premium since rebalance relative to token_owned that pauses writes, 0 = no cap
+    pub premium_since_rebalance: u64,
+    pub collateral_buffer_bps: u64, // collateral locked per notional on writes, BPS_POWER = no buffer
+    pub price_move_threshold_bps: u64, // oracle move that pauses writes, 0 = no write cooldown
+    pub write_cooldown_sec: i64,
+    pub reference_price: OraclePrice, // oracle price moves are measured against
+    pub reference_time: i64,
+    pub write_paused_until: i64,
+    pub lot_size: u64, // locked amounts are multiples of it, 0 = any amount
+    pub snap_to_lot: bool, // snap sub-lot remainders down instead of rejecting the write
+    pub contract_size: u64, // underlying units (the quantity of an option) per contract, 0 = no contract sizing
+    pub claim_window_sec: i64, // time to claim auto exercised profit before it reverts, 0 = no limit
+    pub min_premium_to_collateral_bps: u64, // min premium relative to locked collateral, 0 = no minimum
+    pub settlement_cap_per_epoch: u64, // max payout per settlement epoch, 0 = no cap
+    pub settlement_epoch_sec: i64,
+    pub settlement_epoch_start: i64,
+    pub settled_in_epoch: u64,
+    pub early_exercise_penalty_bps: u64, // payout share kept on exercise right after purchase, 0 = none
+    pub settlement_prices: [SettlementSnapshot; 16], // ring buffer of recent settlements
+    pub settlement_count: u64,
+    pub settlement_window_sec: i64, // settle on the average of reads within this window of expiry, 0 = single read
     // bumps for address validation
     pub bump: u8,
     pub token_account_bump: u8,
@@ -29,6 +72,10 @@ pub struct Custody {
 
 impl Custody {
     pub const LEN: usize = 8 + std::mem::size_of::<Custody>();
+    pub const SETTLEMENT_HISTORY_LEN: usize = 16;
+    // Buybacks refund the unscaled premium, multipliers below 1x would sell options for less
+    pub const MIN_PREMIUM_MULTIPLIER_BPS: u64 = Contract::BPS_POWER as u64;
+    pub const MAX_PREMIUM_MULTIPLIER_BPS: u64 = 10 * Contract::BPS_POWER as u64;
 
     pub fn validate(&self) -> bool {
         self.token_account != Pubkey::default()
@@ -36,6 +83,367 @@ impl Custody {
             && self.oracle != Pubkey::default()
     }
 
+    /// Price of the custody asset on settlement. With min_oracle_feeds set, the median of the
+    /// primary oracle and the backup oracles passed in `backup_oracle_accounts`.
+    pub fn get_oracle_price<'info>(
+        &self,
+        contract: &Contract,
+        current_time: i64,
+        oracle_account: &AccountInfo<'info>,
+        backup_oracle_accounts: &[AccountInfo<'info>],
+    ) -> Result<OraclePrice> {
+        self.check_oracle_kind(oracle_account)?;
+        if self.min_oracle_feeds == 0 {
+            return contract.get_oracle_price(oracle_account, current_time);
+        }
+
+        require_keys_eq!(oracle_account.key(), self.oracle, ContractError::InvalidOracleAccount);
+        let mut oracle_accounts = vec![oracle_account];
+        for backup_oracle_account in backup_oracle_accounts {
+            let key = backup_oracle_account.key();
+            require!(
+                key != Pubkey::default()
+                    && self.backup_oracles.contains(&key)
+                    && !oracle_accounts.iter().any(|account| account.key() == key),
+                ContractError::InvalidOracleAccount
+            );
+            oracle_accounts.push(backup_oracle_account);
+        }
+
+        OraclePrice::new_from_oracles(
+            &oracle_accounts,
+            self.min_oracle_feeds as usize,
+            current_time,
+            contract.max_price_age_sec,
+            contract.max_price_conf_bps,
+        )
+    }
+
+    /// Backup oracles used for a median price, none while min_oracle_feeds is 0.
+    pub fn get_backup_oracle_len(&self) -> usize {
+        if self.min_oracle_feeds == 0 {
+            return 0;
+        }
+        self.backup_oracles
+            .iter()
+            .filter(|key| **key != Pubkey::default())
+            .count()
+    }
+
+    /// Accounts passed next to the primary oracle for a settlement price: each backup oracle
+    /// followed by its settlement print.
+    pub fn get_settlement_backup_len(&self) -> usize {
+        2 * self.get_backup_oracle_len()
+    }
+
+    /// Settlement price of the custody asset for an option expiring at `expired_date`, see
+    /// OraclePrice::new_settlement_price. With min_oracle_feeds set, the median of the
+    /// settlement prints of the primary oracle and of the backup oracles, which are passed in
+    /// `backup_accounts` as laid out by get_settlement_backup_len.
+    pub fn get_settlement_price<'info>(
+        &self,
+        oracle_account: &AccountInfo<'info>,
+        settlement_account: &AccountInfo<'info>,
+        backup_accounts: &[AccountInfo<'info>],
+        expired_date: i64,
+        deferred: bool,
+    ) -> Result<OraclePrice> {
+        if self.min_oracle_feeds == 0 {
+            return OraclePrice::new_settlement_price(
+                oracle_account,
+                settlement_account,
+                expired_date,
+                deferred,
+            );
+        }
+
+        require_keys_eq!(
+            oracle_account.key(),
+            self.oracle,
+            ContractError::InvalidOracleAccount
+        );
+        require_eq!(
+            backup_accounts.len(),
+            self.get_settlement_backup_len(),
+            ContractError::InvalidOracleAccount
+        );
+        let mut feeds = vec![(oracle_account, settlement_account)];
+        for backup_feed in backup_accounts.chunks_exact(2) {
+            let key = backup_feed[0].key();
+            require!(
+                key != Pubkey::default()
+                    && self.backup_oracles.contains(&key)
+                    && !feeds.iter().any(|(account, _)| account.key() == key),
+                ContractError::InvalidOracleAccount
+            );
+            feeds.push((&backup_feed[0], &backup_feed[1]));
+        }
+
+        OraclePrice::new_settlement_price_from_oracles(
+            &feeds,
+            self.min_oracle_feeds as usize,
+            expired_date,
+            deferred,
+        )
+    }
+
+    /// Anchor writes accounts back in declaration order, so when two custody accounts of an
+    /// instruction are the same account only the changes made through the later declared one
+    /// persist. Returns `later` when it is the same account as `earlier`.
+    pub fn last_written<'a, 'info>(
+        earlier: &'a mut Account<'info, Custody>,
+        later: &'a mut Account<'info, Custody>,
+    ) -> &'a mut Account<'info, Custody> {
+        if earlier.key() == later.key() {
+            later
+        } else {
+            earlier
+        }
+    }
+
+    pub fn check_oracle_kind(&self, oracle_account: &AccountInfo) -> Result<()> {
+        require!(
+            OracleKind::of(oracle_account) == self.oracle_kind,
+            ContractError::InvalidOracleAccount
+        );
+        Ok(())
+    }
+
+    pub fn apply_premium_multiplier(&self, premium: f64) -> Result<f64> {
+        math::checked_float_div(
+            math::checked_float_mul(premium, math::checked_as_f64(self.premium_multiplier_bps)?)?,
+            Contract::BPS_POWER as f64,
+        )
+    }
+
+    pub fn apply_collateral_buffer(&self, amount: u64) -> Result<u64> {
+        math::checked_as_u64(math::checked_div(
+            math::checked_mul(amount as u128, self.collateral_buffer_bps as u128)?,
+            Contract::BPS_POWER,
+        )?)
+    }
+
+    /// Rounds a locked amount to the lot size, snapping the remainder down or rejecting
+    /// amounts that aren't a multiple of it depending on snap_to_lot.
+    pub fn apply_lot_size(&self, amount: u64) -> Result<u64> {
+        if self.lot_size == 0 {
+            return Ok(amount);
+        }
+        let remainder = amount % self.lot_size;
+        if remainder != 0 {
+            require!(self.snap_to_lot, OptionError::InvalidLotSizeError);
+            msg!("Snapping locked amount {} down to lot size {}", amount, self.lot_size);
+        }
+        let amount = math::checked_sub(amount, remainder)?;
+        require_gt!(amount, 0, OptionError::InvalidLotSizeError);
+        Ok(amount)
+    }
+
+    pub fn record_settlement_price(&mut self, timestamp: i64, price: OraclePrice) -> Result<()> {
+        let slot = (self.settlement_count % Self::SETTLEMENT_HISTORY_LEN as u64) as usize;
+        self.settlement_prices[slot] = SettlementSnapshot { timestamp, price };
+        self.settlement_count = math::checked_add(self.settlement_count, 1)?;
+        Ok(())
+    }
+
+    // recorded settlements from oldest to newest
+    pub fn get_settlement_prices(&self) -> Vec<SettlementSnapshot> {
+        let history_len = Self::SETTLEMENT_HISTORY_LEN as u64;
+        let first = self.settlement_count.saturating_sub(history_len);
+        (first..self.settlement_count)
+            .map(|i| self.settlement_prices[(i % history_len) as usize])
+            .collect()
+    }
+
+    /// Takes `amount` from the payout budget of the current settlement epoch, false when it
+    /// doesn't fit and the payout has to wait for a later epoch. The first payout of an epoch
+    /// always fits, so payouts larger than the cap are throttled but never stuck.
+    pub fn try_consume_settlement_cap(&mut self, amount: u64, curtime: i64) -> Result<bool> {
+        if self.settlement_cap_per_epoch == 0 {
+            return Ok(true);
+        }
+
+        let epoch_start = math::checked_sub(curtime, curtime % self.settlement_epoch_sec)?;
+        if epoch_start != self.settlement_epoch_start {
+            self.settlement_epoch_start = epoch_start;
+            self.settled_in_epoch = 0;
+        }
+
+        let settled_in_epoch = math::checked_add(self.settled_in_epoch, amount)?;
+        if self.settled_in_epoch > 0 && settled_in_epoch > self.settlement_cap_per_epoch {
+            msg!("Settlement cap reached: {} settled in epoch", self.settled_in_epoch);
+            return Ok(false);
+        }
+        self.settled_in_epoch = settled_in_epoch;
+        Ok(true)
+    }
+
+    /// Penalty on a payout exercised `remaining_sec` before expiry: early_exercise_penalty_bps
+    /// of the payout, scaled down linearly with the remaining share of the tenor to 0 at expiry.
+    pub fn get_early_exercise_penalty(
+        &self,
+        payout: u64,
+        remaining_sec: i64,
+        tenor_sec: i64,
+    ) -> Result<u64> {
+        if self.early_exercise_penalty_bps == 0 || remaining_sec <= 0 || tenor_sec <= 0 {
+            return Ok(0);
+        }
+        let remaining_sec = std::cmp::min(remaining_sec, tenor_sec);
+        let max_penalty = math::checked_div(
+            math::checked_mul(payout as u128, self.early_exercise_penalty_bps as u128)?,
+            Contract::BPS_POWER,
+        )?;
+        math::checked_as_u64(math::checked_div(
+            math::checked_mul(max_penalty, remaining_sec as u128)?,
+            tenor_sec as u128,
+        )?)
+    }
+
+    pub fn is_claim_window_over(&self, exercised: i64, curtime: i64) -> Result<bool> {
+        if self.claim_window_sec == 0 {
+            return Ok(false);
+        }
+        Ok(curtime > math::checked_add(exercised, self.claim_window_sec)?)
+    }
+
+    pub fn is_premium_sufficient(&self, premium_usd: u64, locked_usd: u64) -> Result<bool> {
+        if self.min_premium_to_collateral_bps == 0 {
+            return Ok(true);
+        }
+        let min_premium_usd = math::checked_div(
+            math::checked_mul(locked_usd as u128, self.min_premium_to_collateral_bps as u128)?,
+            Contract::BPS_POWER,
+        )?;
+        Ok(premium_usd as u128 >= min_premium_usd)
+    }
+
+    pub fn is_premium_cap_reached(&self) -> Result<bool> {
+        if self.premium_cap_bps == 0 {
+            return Ok(false);
+        }
+        let premium_cap = math::checked_as_u64(math::checked_div(
+            math::checked_mul(self.token_owned as u128, self.premium_cap_bps as u128)?,
+            Contract::BPS_POWER,
+        )?)?;
+        Ok(self.premium_since_rebalance >= premium_cap)
+    }
+
+    /// Quantity of `contracts` options of contract_size underlying units each
+    pub fn get_contracts_quantity(&self, contracts: u64) -> Result<u64> {
+        require_gt!(self.contract_size, 0, OptionError::InvalidQuantityError);
+        math::checked_mul(contracts, self.contract_size)
+    }
+
+    /// Rounds a quantity down to whole contracts, contract_size has to be set
+    pub fn round_to_contracts(&self, quantity: u64) -> u64 {
+        quantity - quantity % self.contract_size
+    }
+
+    /// Liquidity fees stay in the custody token account on deposits and withdrawals alike, so
+    /// the pool owns the whole deposited amount and only gives up what is transferred out.
+    pub fn credit_deposit(&mut self, amount_in: u64) -> Result<()> {
+        self.token_owned = math::checked_add(self.token_owned, amount_in)?;
+        Ok(())
+    }
+
+    /// See `credit_deposit`, `amount_out` is net of the withdrawal fee.
+    pub fn debit_withdrawal(&mut self, amount_out: u64) -> Result<()> {
+        self.token_owned = math::checked_sub(self.token_owned, amount_out)?;
+        Ok(())
+    }
+
+    pub fn collect_premium(&mut self, amount: u64) -> Result<()> {
+        self.token_owned = math::checked_add(self.token_owned, amount)?;
+        self.premium_since_rebalance = math::checked_add(self.premium_since_rebalance, amount)?;
+        Ok(())
+    }
+
+    /// Tracks oracle moves of the custody asset. A move of more than price_move_threshold_bps
+    /// from the reference price recorded within the last write_cooldown_sec pauses option
+    /// writes for write_cooldown_sec, giving pricing inputs time to catch up.
+    pub fn update_price_move(&mut self, price: &OraclePrice, curtime: i64) -> Result<()> {
+        if self.price_move_threshold_bps == 0 {
+            return Ok(());
+        }
+
+        let reference_expiry = math::checked_add(self.reference_time, self.write_cooldown_sec)?;
+        if self.reference_price.price != 0 && curtime < reference_expiry {
+            let price = price.scale_to_exponent(self.reference_price.exponent)?;
+            let move_bps = math::checked_div(
+                math::checked_mul(
+                    self.reference_price.price.abs_diff(price.price) as u128,
+                    Contract::BPS_POWER,
+                )?,
+                self.reference_price.price as u128,
+            )?;
+            if move_bps > self.price_move_threshold_bps as u128 {
+                msg!("Oracle moved {} bps, pausing writes", move_bps);
+                self.write_paused_until = math::checked_add(curtime, self.write_cooldown_sec)?;
+            }
+        }
+
+        self.reference_price = *price;
+        self.reference_time = curtime;
+        Ok(())
+    }
+
+    pub fn is_write_paused(&self, curtime: i64) -> bool {
+        curtime < self.write_paused_until
+    }
+
+    pub fn add_claim(&mut self, amount: u64) -> Result<()> {
+        self.token_claimable = math::checked_add(self.token_claimable, amount)?;
+        Ok(())
+    }
+
+    pub fn settle_claim(&mut self, amount: u64) {
+        self.token_claimable = self.token_claimable.saturating_sub(amount);
+    }
+
+    /// Books a cash payout of `amount`. Within the settlement cap of the epoch it leaves the
+    /// custody right away (true), past it the payout is queued as a claim (false).
+    pub fn book_payout(&mut self, amount: u64, curtime: i64) -> Result<bool> {
+        if self.try_consume_settlement_cap(amount, curtime)? {
+            self.token_owned = math::checked_sub(self.token_owned, amount)?;
+            Ok(true)
+        } else {
+            self.add_claim(amount)?;
+            Ok(false)
+        }
+    }
+
+    /// Pays out a queued claim, the tokens leave the custody
+    pub fn pay_claim(&mut self, amount: u64) -> Result<()> {
+        self.token_owned = math::checked_sub(self.token_owned, amount)?;
+        self.settle_claim(amount);
+        Ok(())
+    }
+
+    pub fn add_reserve(&mut self, amount: u64) -> Result<()> {
+        self.token_reserved = math::checked_add(self.token_reserved, amount)?;
+        Ok(())
+    }
+
+    /// Reserve paid out of the custody by withdraw_reserve
+    pub fn take_reserve(&mut self, amount: u64) -> Result<()> {
+        self.token_reserved = math::checked_sub(self.token_reserved, amount)?;
+        self.token_owned = math::checked_sub(self.token_owned, amount)?;
+        Ok(())
+    }
+
+    // tokens backing LP shares, the reserve is held in the custody but doesn't belong to LPs
+    pub fn get_lp_owned_amount(&self) -> Result<u64> {
+        math::checked_sub(self.token_owned, self.token_reserved)
+    }
+
+    // tokens LPs can withdraw, net of locked collateral, outstanding claims and the reserve
+    pub fn get_withdrawable_amount(&self) -> Result<u64> {
+        Ok(math::checked_sub(self.token_owned, self.token_locked)?
+            .saturating_sub(self.token_claimable)
+            .saturating_sub(self.token_reserved))
+    }
+
     pub fn lock_funds(&mut self, amount: u64) -> Result<()> {
         self.token_locked = math::checked_add(self.token_locked, amount)?;
         if self.token_owned < self.token_locked {
@@ -55,3 +463,203 @@ impl Custody {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountsExit;
+
+    fn price(price: u64) -> OraclePrice {
+        OraclePrice::new(price * 100_000_000, -8)
+    }
+
+    fn price_move_custody() -> Custody {
+        Custody {
+            price_move_threshold_bps: 500,
+            write_cooldown_sec: 60,
+            ..Default::default()
+        }
+    }
+
+    // serialized custody account owned by the program, for several Account copies of it
+    fn custody_account_data(custody: &Custody) -> Vec<u8> {
+        let mut data = vec![0; Custody::LEN];
+        custody.try_serialize(&mut &mut data[..]).unwrap();
+        data
+    }
+
+    #[test]
+    fn premium_multiplier_scales_the_premium_linearly() {
+        let mut custody = Custody::default();
+        for (premium_multiplier_bps, premium) in
+            [(10_000, 2.5), (15_000, 3.75), (20_000, 5.0), (100_000, 25.0)]
+        {
+            custody.premium_multiplier_bps = premium_multiplier_bps;
+            assert!((custody.apply_premium_multiplier(2.5).unwrap() - premium).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn sharp_price_moves_pause_writes_until_the_cooldown_ends() {
+        let mut custody = price_move_custody();
+        custody.update_price_move(&price(100), 0).unwrap();
+        custody.update_price_move(&price(104), 10).unwrap();
+        assert!(!custody.is_write_paused(10));
+
+        // 110 is 576 bps off the 104 recorded 10 seconds earlier
+        custody.update_price_move(&price(110), 20).unwrap();
+        assert!(custody.is_write_paused(20));
+        assert!(custody.is_write_paused(79));
+        assert!(!custody.is_write_paused(80));
+    }
+
+    #[test]
+    fn price_moves_persist_when_custody_is_the_locked_custody() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = custody_account_data(&price_move_custody());
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &crate::ID, false, 0);
+        let mut custody = Account::<Custody>::try_from(&info).unwrap();
+        let mut locked_custody = Account::<Custody>::try_from(&info).unwrap();
+
+        let price_move_custody = Custody::last_written(&mut custody, &mut locked_custody);
+        price_move_custody.update_price_move(&price(100), 0).unwrap();
+        price_move_custody.update_price_move(&price(110), 10).unwrap();
+
+        // written back in declaration order, as at the end of open_option
+        custody.exit(&crate::ID).unwrap();
+        locked_custody.exit(&crate::ID).unwrap();
+        let custody = Account::<Custody>::try_from(&info).unwrap();
+        assert!(custody.is_write_paused(10));
+        assert!(!custody.is_write_paused(70));
+    }
+
+    #[test]
+    fn premiums_persist_when_pay_custody_is_the_locked_custody() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = custody_account_data(&Custody {
+            token_owned: 1_000,
+            premium_cap_bps: 1_000,
+            ..Default::default()
+        });
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &crate::ID, false, 0);
+        let mut pay_custody = Account::<Custody>::try_from(&info).unwrap();
+        let mut locked_custody = Account::<Custody>::try_from(&info).unwrap();
+
+        Custody::last_written(&mut pay_custody, &mut locked_custody)
+            .collect_premium(150)
+            .unwrap();
+        locked_custody.lock_funds(500).unwrap();
+
+        pay_custody.exit(&crate::ID).unwrap();
+        locked_custody.exit(&crate::ID).unwrap();
+        let custody = Account::<Custody>::try_from(&info).unwrap();
+        assert_eq!(custody.token_owned, 1_150);
+        assert_eq!(custody.token_locked, 500);
+        assert_eq!(custody.premium_since_rebalance, 150);
+        assert!(custody.is_premium_cap_reached().unwrap());
+    }
+
+    #[test]
+    fn liquidity_round_trips_keep_token_owned_equal_to_the_vault() {
+        let mut custody = Custody::default();
+        let mut vault = 0u64;
+
+        // 1_000 deposited with a 10 fee, 495 withdrawn net of a 5 fee
+        vault += 1_000;
+        custody.credit_deposit(1_000).unwrap();
+        vault -= 495;
+        custody.debit_withdrawal(495).unwrap();
+
+        assert_eq!(custody.token_owned, vault);
+        assert_eq!(custody.token_owned, 505);
+    }
+
+    #[test]
+    fn deposit_then_withdraw_leaves_token_owned_unchanged() {
+        let mut custody = Custody {
+            token_owned: 7_000,
+            ..Default::default()
+        };
+
+        custody.credit_deposit(1_000).unwrap();
+        custody.debit_withdrawal(1_000).unwrap();
+
+        assert_eq!(custody.token_owned, 7_000);
+        assert!(custody.debit_withdrawal(7_001).is_err());
+    }
+
+    #[test]
+    fn options_sized_by_contracts_fill_whole_contracts() {
+        let custody = Custody {
+            contract_size: 100,
+            ..Default::default()
+        };
+
+        assert_eq!(custody.get_contracts_quantity(3).unwrap(), 300);
+        assert_eq!(custody.round_to_contracts(250), 200);
+        assert_eq!(custody.round_to_contracts(99), 0);
+        assert!(Custody::default().get_contracts_quantity(3).is_err());
+    }
+
+    #[test]
+    fn cash_payouts_leave_token_owned_when_paid() {
+        // 100 per hourly epoch
+        let mut custody = Custody {
+            token_owned: 1_000,
+            settlement_cap_per_epoch: 100,
+            settlement_epoch_sec: 3_600,
+            ..Default::default()
+        };
+        let epoch = 1_699_999_200;
+
+        // within the cap the payout leaves the custody at once
+        assert!(custody.book_payout(80, epoch).unwrap());
+        assert_eq!(custody.token_owned, 920);
+
+        // past it the payout is queued and only leaves once the claim is paid
+        assert!(!custody.book_payout(50, epoch + 60).unwrap());
+        assert_eq!(custody.token_owned, 920);
+        assert_eq!(custody.token_claimable, 50);
+        assert_eq!(custody.get_withdrawable_amount().unwrap(), 870);
+
+        assert!(custody.try_consume_settlement_cap(50, epoch + 3_600).unwrap());
+        custody.pay_claim(50).unwrap();
+        assert_eq!(custody.token_owned, 870);
+        assert_eq!(custody.token_claimable, 0);
+        assert_eq!(custody.get_withdrawable_amount().unwrap(), 870);
+    }
+
+    #[test]
+    fn overdrawn_balances_fail_with_the_math_error() {
+        let mut custody = Custody {
+            token_owned: 100,
+            token_claimable: 100,
+            ..Default::default()
+        };
+        let overflow: Error = crate::errors::MathError::OverflowMathError.into();
+
+        assert_eq!(custody.debit_withdrawal(101).unwrap_err(), overflow);
+        assert_eq!(custody.pay_claim(101).unwrap_err(), overflow);
+        assert_eq!(custody.book_payout(101, 0).unwrap_err(), overflow);
+        assert_eq!(custody.token_owned, 100);
+    }
+
+    #[test]
+    fn the_reserve_is_left_out_of_lp_funds_until_withdrawn() {
+        let mut custody = Custody {
+            token_owned: 1_000,
+            ..Default::default()
+        };
+        custody.add_reserve(30).unwrap();
+        assert_eq!(custody.get_lp_owned_amount().unwrap(), 970);
+        assert_eq!(custody.get_withdrawable_amount().unwrap(), 970);
+
+        custody.take_reserve(20).unwrap();
+        assert_eq!(custody.token_owned, 980);
+        assert_eq!(custody.token_reserved, 10);
+        assert_eq!(custody.get_lp_owned_amount().unwrap(), 970);
+        assert!(custody.take_reserve(11).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::PoolError, math};
+
+#[account]
+#[derive(Default, Debug)]
+pub struct LpPosition {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub deposited_usd: u64, // cumulative deposits of the owner into the pool
+    pub bump: u8,
+}
+
+impl LpPosition {
+    pub const LEN: usize = 8 + std::mem::size_of::<LpPosition>();
+
+    /// Adds a deposit, `max_deposit_usd` bounds the cumulative deposits, 0 = no cap
+    pub fn record_deposit(&mut self, amount_usd: u64, max_deposit_usd: u64) -> Result<()> {
+        let deposited_usd = math::checked_add(self.deposited_usd, amount_usd)?;
+        if max_deposit_usd > 0 {
+            require_gte!(max_deposit_usd, deposited_usd, PoolError::DepositCapExceeded);
+        }
+        self.deposited_usd = deposited_usd;
+        Ok(())
+    }
+}
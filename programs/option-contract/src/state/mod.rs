@@ -5,6 +5,8 @@ pub use contract::*;
 pub use oracle::*;
 pub use pool::*;
 pub use custody::*;
+pub use lp_position::*;
+pub use option_book::*;
 
 pub mod option;
 pub mod user;
@@ -12,4 +14,6 @@ pub mod multisig;
 pub mod contract;
 pub mod oracle;
 pub mod pool;
-pub mod custody;
\ No newline at end of file
+pub mod custody;
+pub mod lp_position;
+pub mod option_book;
\ No newline at end of file
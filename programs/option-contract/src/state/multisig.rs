@@ -19,6 +19,13 @@ pub struct Multisig {
     pub bump: u8,
 }
 
+#[event]
+pub struct AdminInstructionExecuted {
+    pub instruction: u8, // AdminInstruction
+    pub accounts: Vec<Pubkey>, // instruction accounts after the signer
+    pub params: Vec<u8>, // serialized instruction params
+}
+
 pub enum AdminInstruction {
     AddPool,
     RemovePool,
@@ -33,6 +40,44 @@ pub enum AdminInstruction {
     SetCustomOraclePrice,
     SetTestTime,
     UpgradeCustody,
+    SetPremiumMultiplier,
+    SetPremiumCap,
+    RebalanceAck,
+    SetCollateralBuffer,
+    SetWriteCooldown,
+    FreezeOption,
+    UnfreezeOption,
+    SetLotSize,
+    SetAllowedPremiumMints,
+    SetClaimWindow,
+    SetMinPremiumRatio,
+    AddFeeExempt,
+    RemoveFeeExempt,
+    SetBackupOracles,
+    SetSettlementCap,
+    SetEarlyExercisePenalty,
+    SetQuoteCustody,
+    SetRentBuffer,
+    SetMaxDecimalsDiff,
+    RepairUserIndex,
+    SetVolatility,
+    SetSettlementWindow,
+    AddKeeper,
+    RemoveKeeper,
+    SetRiskGroup,
+    SetProtocolFee,
+    SetDepositCap,
+    SetMinExerciseGap,
+    SetAutoExerciseReserve,
+    SetContractSize,
+    SetPaused,
+    SetMaxOpenOptions,
+    SetMaxUtilization,
+    SetMinPremium,
+    UpdateContractParams,
+    SetPricingModel,
+    SetMakerRebate,
+    WithdrawReserve,
 }
 
 impl Multisig {
@@ -132,6 +177,29 @@ impl Multisig {
         signer_account: &AccountInfo,
         instruction_accounts: &[AccountInfo],
         instruction_data: &[u8],
+    ) -> Result<u8> {
+        let signatures_left =
+            self.count_signature(signer_account, instruction_accounts, instruction_data)?;
+
+        // the instruction executes once fully signed, log it for indexers
+        if signatures_left == 0 {
+            if let Some((&instruction, params)) = instruction_data.split_last() {
+                emit!(AdminInstructionExecuted {
+                    instruction,
+                    accounts: instruction_accounts.iter().map(|account| account.key()).collect(),
+                    params: params.to_vec(),
+                });
+            }
+        }
+
+        Ok(signatures_left)
+    }
+
+    fn count_signature(
+        &mut self,
+        signer_account: &AccountInfo,
+        instruction_accounts: &[AccountInfo],
+        instruction_data: &[u8],
     ) -> Result<u8> {
         // return early if not a signer
         if !signer_account.is_signer {
@@ -1,12 +1,35 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    errors::OptionError,
+    math,
+    state::{Contract, Custody, OraclePrice},
+};
+
+#[derive(Copy, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
+pub enum SettlementKind {
+    #[default]
+    Cash, // profit paid out in the locked token
+    Physical, // calls only, the holder pays the strike in the quote asset for the underlying
+}
+
+#[derive(Copy, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
+pub enum PricingModel {
+    #[default]
+    BlackScholes,
+    Approximation, // Bachelier time value with a polynomial decay, no exp/ln, for cheap writes
+}
+
 #[account]
+#[derive(Default, Debug)]
 pub struct OptionDetail {
     pub index: u64,
     pub owner: Pubkey,
-    pub amount: u64,
-    pub quantity: u64,
-    pub strike_price: f64,
+    pub amount: u64, // collateral locked in the locked custody, in locked tokens
+    pub quantity: u64, // contract size, options the amount backs
+    pub strike_price: u64, // scaled by strike_exponent
+    pub strike_exponent: i32, // custody strike exponent at write time
+    pub breakeven_price: u64, // strike -/+ per unit premium at expiry, scaled by strike_exponent
     pub period: u64,
     pub expired_date: i64,
     pub purchase_date: u64,
@@ -27,10 +50,39 @@ pub struct OptionDetail {
     pub bump: u8,
     pub limit_price: u64,
     pub executed: bool,
+    pub premium_split_bps: u64, // part of the premium paid with the secondary asset
+    pub secondary_premium_asset: Pubkey, // secondary pay_custody key
+    pub secondary_premium_amount: u64, // amount paid in the secondary asset
+    pub settlement_frozen: bool, // settlement paused by admins pending dispute
+    pub pending_settlement: bool, // expired during an oracle outage, settles after recovery
+    pub forfeited: bool, // profit was not claimed within the claim window and reverted to the pool
+    pub last_mark: u64, // fair value in usd of the position at last_mark_time
+    pub last_mark_time: i64,
+    pub auto_exercise_trigger_price: Option<u64>, // scaled by strike_exponent, keepers may exercise once crossed
+    pub max_profit: Option<u64>, // payout cap of the position in locked tokens, None - uncapped
+    pub settlement_price_sum: u128, // oracle reads around expiry, scaled by strike_exponent
+    pub settlement_reads: u8,
+    pub settlement_last_publish: i64, // publish time of the last recorded read
+    pub is_american: bool, // exercisable before expiry, european options only settle at expiry
+    pub created_slot: u64, // slot the option was written in
+    pub settlement: SettlementKind, // of exercises before expiry, options settle in cash at expiry
+    pub profit_cap: Option<u64>, // max profit per unit in usd (USD_DECIMALS), None - uncapped
+    pub book_page: u32, // page of the pool option book listing the option, see OptionBookPage
 }
 
 impl OptionDetail {
-    pub const LEN: usize = 8 * 13 + 1 * 4 + 32 * 5 + 8;
+    pub const LEN: usize = 8 * 19 + 4 + 1 * 8 + 32 * 6 + 8 + (1 + 8) * 3 + 16 + 1 + 8 + 1 + 4;
+    pub const VOLATILITY: f64 = 0.5; // starting point of the implied volatility solver
+    pub const MIN_IMPLIED_VOLATILITY: f64 = 0.0001;
+    pub const MAX_IMPLIED_VOLATILITY: f64 = 10.0;
+    const IMPLIED_VOLATILITY_ITERATIONS: u32 = 50;
+    pub const MAX_CLOSE_BATCH: usize = 16; // options closed per close_options_batch, bounds the compute
+    pub const MAX_AUTO_EXERCISE_BATCH: usize = 4; // options settled per auto_exercise_batch
+    pub const EXPIRY_TOLERANCE_SEC: i64 = 86400; // expiries may snap to a daily fixing time
+    pub const APPROXIMATION_CHECK_PERIOD_YEAR: f64 = 30.0 / 365.0; // tenor the approximation is checked at
+    const INV_SQRT_2PI: f64 = 0.398_942_280_401_432_7;
+    // time value decays to zero this many stddevs from the money, the exact slope there is 1/2
+    const APPROXIMATION_DECAY_WIDTH: f64 = 4.0 * Self::INV_SQRT_2PI;
 
     pub fn normal_cdf(z: f64) -> f64 {
         let beta1 = -0.0004406;
@@ -41,15 +93,51 @@ impl OptionDetail {
         1.0 / (1.0 + exponent.exp())
     }
     
+    /// Prices a european option in usd.
+    /// Supported inputs: spot `s` and strike `k` are positive finite usd prices and `t` is a
+    /// positive finite time to expiry in years. The risk free rate is fixed at 0, so negative
+    /// carry or inverted quotes are rejected with `InvalidPricingInputError`.
     pub fn black_scholes(
         s: f64,
         k: f64,
         t: f64,
         call: bool, // true : call , false : put
-    ) -> f64 {
+        volatility_bps: u16, // annualized volatility, custody volatility_bps
+    ) -> Result<f64> {
+        OptionDetail::black_scholes_with_volatility(
+            s,
+            k,
+            t,
+            volatility_bps as f64 / Contract::BPS_POWER as f64,
+            call,
+        )
+    }
+
+    /// Same as `black_scholes` at the annualized volatility `sigma`.
+    pub fn black_scholes_with_volatility(
+        s: f64,
+        k: f64,
+        t: f64,
+        sigma: f64,
+        call: bool, // true : call , false : put
+    ) -> Result<f64> {
+        if !(s.is_finite() && s > 0.0)
+            || !(k.is_finite() && k > 0.0)
+            || !(t.is_finite() && t > 0.0)
+            || !(sigma.is_finite() && sigma > 0.0)
+        {
+            msg!(
+                "Error: Unsupported Black-Scholes inputs s: {}, k: {}, t: {}, sigma: {}",
+                s,
+                k,
+                t,
+                sigma
+            );
+            return err!(OptionError::InvalidPricingInputError);
+        }
+
         let r = 0.0;
-        let sigma = 0.5;
-        let d1 = ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt());
+        let d1 = OptionDetail::black_scholes_d1(s, k, t, r, sigma);
         let d2 = d1 - sigma * t.sqrt();
     
         let n_d1 = OptionDetail::normal_cdf(d1);
@@ -57,10 +145,751 @@ impl OptionDetail {
         let n_neg_d1 = OptionDetail::normal_cdf(-d1);
         let n_neg_d2 = OptionDetail::normal_cdf(-d2);
     
-        if call {
+        let price = if call {
             s * n_d1 - k * (-r * t).exp() * n_d2
         } else {
             k * (-r * t).exp() * n_neg_d2 - s * n_neg_d1
+        };
+        if !price.is_finite() {
+            msg!("Error: Black-Scholes price is not finite for s: {}, k: {}, t: {}", s, k, t);
+            return err!(OptionError::InvalidPricingInputError);
+        }
+
+        // normal_cdf is an approximation and may undershoot zero deep out of the money
+        Ok(price.max(0.0))
+    }
+
+    /// Premium in usd of the pricing model of the custody, see `black_scholes` for the inputs.
+    pub fn get_option_price(
+        pricing_model: PricingModel,
+        s: f64,
+        k: f64,
+        t: f64,
+        call: bool, // true : call , false : put
+        volatility_bps: u16, // annualized volatility, custody volatility_bps
+    ) -> Result<f64> {
+        match pricing_model {
+            PricingModel::BlackScholes => OptionDetail::black_scholes(s, k, t, call, volatility_bps),
+            PricingModel::Approximation => {
+                OptionDetail::approximate_price(s, k, t, call, volatility_bps)
+            }
+        }
+    }
+
+    /// Premium in usd of an option paying out at most `profit_cap` (usd per unit, USD_DECIMALS)
+    /// per unit: the spread between the option at the strike and the one at the cap strike.
+    pub fn get_capped_option_price(
+        pricing_model: PricingModel,
+        s: f64,
+        strike_price: &OraclePrice,
+        t: f64,
+        call: bool, // true : call , false : put
+        volatility_bps: u16, // annualized volatility, custody volatility_bps
+        profit_cap: Option<u64>, // None - uncapped
+    ) -> Result<f64> {
+        let price = OptionDetail::get_option_price(
+            pricing_model,
+            s,
+            strike_price.checked_as_f64()?,
+            t,
+            call,
+            volatility_bps,
+        )?;
+        let Some(profit_cap) = profit_cap else {
+            return Ok(price);
+        };
+        let cap_strike = OptionDetail::get_cap_strike(strike_price, call, profit_cap)?;
+        let cap_price = OptionDetail::get_option_price(
+            pricing_model,
+            s,
+            cap_strike.checked_as_f64()?,
+            t,
+            call,
+            volatility_bps,
+        )?;
+        Ok((price - cap_price).max(0.0))
+    }
+
+    /// Intrinsic value plus the at the money Bachelier time value `s * sigma * sqrt(t) / sqrt(2 pi)`,
+    /// decaying with the moneyness `d` in stddevs as `(1 - |d| / c)^2`. `c` matches the slope of
+    /// the exact time value at the money, so the price agrees with Black-Scholes there and only
+    /// takes a square root. Far from the money it keeps the intrinsic value only.
+    pub fn approximate_price(
+        s: f64,
+        k: f64,
+        t: f64,
+        call: bool, // true : call , false : put
+        volatility_bps: u16, // annualized volatility, custody volatility_bps
+    ) -> Result<f64> {
+        let sigma = volatility_bps as f64 / Contract::BPS_POWER as f64;
+        if !(s.is_finite() && s > 0.0)
+            || !(k.is_finite() && k > 0.0)
+            || !(t.is_finite() && t > 0.0)
+            || !(sigma > 0.0)
+        {
+            msg!(
+                "Error: Unsupported approximation inputs s: {}, k: {}, t: {}, sigma: {}",
+                s,
+                k,
+                t,
+                sigma
+            );
+            return err!(OptionError::InvalidPricingInputError);
+        }
+
+        let stddev = s * sigma * t.sqrt();
+        let decay =
+            (1.0 - ((s - k) / stddev).abs() / OptionDetail::APPROXIMATION_DECAY_WIDTH).max(0.0);
+        let time_value = stddev * OptionDetail::INV_SQRT_2PI * decay * decay;
+        let intrinsic_value = if call { (s - k).max(0.0) } else { (k - s).max(0.0) };
+
+        let price = intrinsic_value + time_value;
+        if !price.is_finite() {
+            msg!("Error: Approximated price is not finite for s: {}, k: {}, t: {}", s, k, t);
+            return err!(OptionError::InvalidPricingInputError);
+        }
+        Ok(price)
+    }
+
+    /// Whether the approximation stays within `tolerance_bps` of Black-Scholes for an at the
+    /// money option over APPROXIMATION_CHECK_PERIOD_YEAR at `volatility_bps`.
+    pub fn is_approximation_within(volatility_bps: u16, tolerance_bps: u16) -> Result<bool> {
+        let t = OptionDetail::APPROXIMATION_CHECK_PERIOD_YEAR;
+        let exact = OptionDetail::black_scholes(1.0, 1.0, t, true, volatility_bps)?;
+        let approximated = OptionDetail::approximate_price(1.0, 1.0, t, true, volatility_bps)?;
+        let diff_bps = (approximated - exact).abs() / exact * Contract::BPS_POWER as f64;
+        Ok(diff_bps <= tolerance_bps as f64)
+    }
+
+    /// Sensitivity of the Black-Scholes price to volatility, same for calls and puts.
+    pub fn bs_vega(s: f64, k: f64, t: f64, sigma: f64) -> f64 {
+        let d1 = OptionDetail::black_scholes_d1(s, k, t, 0.0, sigma);
+        s * (-0.5 * d1 * d1).exp() / (2.0 * std::f64::consts::PI).sqrt() * t.sqrt()
+    }
+
+    /// Inverts `black_scholes_with_volatility` for the volatility at which the option is worth
+    /// `market_price`, using Newton-Raphson on `bs_vega`. Prices outside the no-arbitrage
+    /// bounds or solves that don't converge within the supported volatility range fail with
+    /// `ImpliedVolatilityError`.
+    pub fn implied_volatility(
+        s: f64,
+        k: f64,
+        t: f64,
+        call: bool, // true : call , false : put
+        market_price: f64,
+    ) -> Result<f64> {
+        // price has to be above intrinsic value and below the spot (call) or strike (put)
+        let (lower_bound, upper_bound) = if call {
+            ((s - k).max(0.0), s)
+        } else {
+            ((k - s).max(0.0), k)
+        };
+        if !(market_price.is_finite() && market_price > lower_bound && market_price < upper_bound)
+        {
+            msg!(
+                "Error: Premium {} outside of the no-arbitrage bounds ({}, {})",
+                market_price,
+                lower_bound,
+                upper_bound
+            );
+            return err!(OptionError::ImpliedVolatilityError);
+        }
+
+        let tolerance = market_price * 1e-9;
+        let mut sigma = OptionDetail::VOLATILITY;
+        for _ in 0..OptionDetail::IMPLIED_VOLATILITY_ITERATIONS {
+            let price_diff =
+                OptionDetail::black_scholes_with_volatility(s, k, t, sigma, call)? - market_price;
+            if price_diff.abs() <= tolerance {
+                return Ok(sigma);
+            }
+            let vega = OptionDetail::bs_vega(s, k, t, sigma);
+            if !(vega.is_finite() && vega > f64::EPSILON) {
+                break;
+            }
+            sigma = (sigma - price_diff / vega).clamp(
+                OptionDetail::MIN_IMPLIED_VOLATILITY,
+                OptionDetail::MAX_IMPLIED_VOLATILITY,
+            );
+        }
+
+        msg!("Error: Implied volatility did not converge for premium {}", market_price);
+        err!(OptionError::ImpliedVolatilityError)
+    }
+
+    /// Fixed-point usd value (USD_DECIMALS) of a Black-Scholes premium. This is the only
+    /// rounding of the float pricing, token amounts are derived from it with integer math so
+    /// every validator computes the same amounts. Premiums rounding to zero are rejected.
+    pub fn get_premium_usd(premium: f64) -> Result<u64> {
+        let premium_usd = math::checked_as_u64(math::checked_float_mul(
+            premium,
+            math::checked_powi(10.0, Contract::USD_DECIMALS as i32)?,
+        )?)?;
+        require_gt!(premium_usd, 0, OptionError::InvalidPriceRequirementError);
+        Ok(premium_usd)
+    }
+
+    /// Buyback value of `quantity` contracts in locked tokens. Only the model price per contract
+    /// is a float, it is rounded down once to usd decimals and converted with integer math.
+    pub fn get_buyback_amount(
+        option_price: f64,
+        quantity: u64,
+        locked_price: &OraclePrice,
+        locked_decimals: u8,
+    ) -> Result<u64> {
+        let option_price_usd = math::checked_as_u64(math::checked_float_mul(
+            option_price,
+            math::checked_powi(10.0, Contract::USD_DECIMALS as i32)?,
+        )?)?;
+        locked_price.get_token_amount(
+            math::checked_mul(option_price_usd, quantity)?,
+            locked_decimals,
+        )
+    }
+
+    pub fn get_strike_price(&self) -> OraclePrice {
+        OraclePrice::new(self.strike_price, self.strike_exponent)
+    }
+
+    /// Breakeven price at expiry in strike terms: strike plus the per unit premium for calls,
+    /// strike minus it for puts (floored at 0). `premium_price` is the usd value of the premium
+    /// paid per unit.
+    pub fn get_breakeven_price(&self, premium_price: &OraclePrice) -> Result<u64> {
+        let premium_price = premium_price.scale_to_exponent(self.strike_exponent)?;
+        if self.option_type == 0 {
+            math::checked_add(self.strike_price, premium_price.price)
+        } else {
+            Ok(self.strike_price.saturating_sub(premium_price.price))
+        }
+    }
+
+    fn black_scholes_d1(s: f64, k: f64, t: f64, r: f64, sigma: f64) -> f64 {
+        ((s / k).ln() + (r + 0.5 * sigma * sigma) * t) / (sigma * t.sqrt())
+    }
+
+    /// Delta of the holder per unit of underlying at `oracle_price`: N(d1) for calls and
+    /// N(d1) - 1 for puts over the remaining tenor, 1 / -1 / 0 by moneyness once expired.
+    pub fn get_delta(&self, oracle_price: f64, curtime: i64, volatility_bps: u16) -> Result<f64> {
+        let is_call = self.option_type == 0;
+        let strike_price = self.get_strike_price().checked_as_f64()?;
+        let remaining_seconds = self.expired_date.saturating_sub(curtime);
+        if remaining_seconds <= 0 {
+            let in_the_money = if is_call {
+                oracle_price > strike_price
+            } else {
+                oracle_price < strike_price
+            };
+            return Ok(match (in_the_money, is_call) {
+                (false, _) => 0.0,
+                (true, true) => 1.0,
+                (true, false) => -1.0,
+            });
+        }
+
+        let t = remaining_seconds as f64 / (365.0 * 86400.0);
+        let sigma = volatility_bps as f64 / Contract::BPS_POWER as f64;
+        if !(oracle_price.is_finite() && oracle_price > 0.0) || !(strike_price > 0.0) || !(sigma > 0.0)
+        {
+            msg!("Error: Unsupported delta inputs s: {}, k: {}", oracle_price, strike_price);
+            return err!(OptionError::InvalidPricingInputError);
+        }
+        let n_d1 = OptionDetail::normal_cdf(OptionDetail::black_scholes_d1(
+            oracle_price,
+            strike_price,
+            t,
+            0.0,
+            sigma,
+        ));
+        Ok(if is_call { n_d1 } else { n_d1 - 1.0 })
+    }
+
+    /// Fair value in usd of the whole position at `oracle_price`: Black-Scholes over the
+    /// remaining tenor, or the intrinsic value once expired.
+    pub fn get_fair_value_usd(
+        &self,
+        oracle_price: f64,
+        curtime: i64,
+        volatility_bps: u16,
+    ) -> Result<f64> {
+        let is_call = self.option_type == 0;
+        let strike_price = self.get_strike_price().checked_as_f64()?;
+        let remaining_seconds = self.expired_date.saturating_sub(curtime);
+        let value_per_contract = if remaining_seconds > 0 {
+            let remaining_years = remaining_seconds as f64 / (365.0 * 86400.0);
+            OptionDetail::black_scholes(
+                oracle_price,
+                strike_price,
+                remaining_years,
+                is_call,
+                volatility_bps,
+            )?
+        } else if is_call {
+            (oracle_price - strike_price).max(0.0)
+        } else {
+            (strike_price - oracle_price).max(0.0)
+        };
+        math::checked_float_mul(value_per_contract, math::checked_as_f64(self.quantity)?)
+    }
+
+    /// At the money policy: an option is only in the money when the oracle price is strictly
+    /// past the strike. At the money options are treated as out of the money everywhere, they
+    /// expire worthless, pay the holder nothing and release all their collateral to the pool.
+    pub fn is_in_the_money(&self, oracle_price: &OraclePrice) -> bool {
+        let strike_price = self.get_strike_price();
+        if self.option_type == 0 {
+            *oracle_price > strike_price
+        } else {
+            *oracle_price < strike_price
+        }
+    }
+
+    /// Take-profit trigger: calls trigger at or above the trigger price, puts at or below it.
+    pub fn is_trigger_crossed(&self, oracle_price: &OraclePrice) -> bool {
+        let Some(trigger_price) = self.auto_exercise_trigger_price else {
+            return false;
+        };
+        let trigger_price = OraclePrice::new(trigger_price, self.strike_exponent);
+        if self.option_type == 0 {
+            *oracle_price >= trigger_price
+        } else {
+            *oracle_price <= trigger_price
+        }
+    }
+
+    /// Settlement price of the averaged mode: mean of the reads recorded around expiry.
+    pub fn get_average_settlement_price(&self) -> Result<OraclePrice> {
+        require_gt!(self.settlement_reads, 0, OptionError::InvalidPriceRequirementError);
+        let price = math::checked_as_u64(math::checked_div(
+            self.settlement_price_sum,
+            self.settlement_reads as u128,
+        )?)?;
+        Ok(OraclePrice::new(price, self.strike_exponent))
+    }
+
+    /// Price the option expired at: the average of the reads recorded around expiry in the
+    /// averaged mode of `custody`, otherwise the first prints after expiry, see
+    /// Custody::get_settlement_price.
+    pub fn get_expiry_price<'info>(
+        &self,
+        custody: &Custody,
+        oracle_account: &AccountInfo<'info>,
+        settlement_account: &AccountInfo<'info>,
+        backup_accounts: &[AccountInfo<'info>],
+    ) -> Result<OraclePrice> {
+        if custody.settlement_window_sec > 0 && self.settlement_reads > 0 {
+            self.get_average_settlement_price()
+        } else {
+            custody.get_settlement_price(
+                oracle_account,
+                settlement_account,
+                backup_accounts,
+                self.expired_date,
+                self.pending_settlement,
+            )
+        }
+    }
+
+    /// Settled options without profit left to claim, their account can be closed
+    pub fn is_closable(&self) -> bool {
+        !self.valid && self.claimed == 0
+    }
+
+    /// Checks the expiry of a new option is in the future and matches its `period` in days,
+    /// the Black-Scholes time input.
+    pub fn validate_expiry(period: u64, expired_time: u64, curtime: i64) -> Result<()> {
+        require_gt!(period, 0, OptionError::InvalidTimeError);
+        let expired_time =
+            i64::try_from(expired_time).map_err(|_| OptionError::InvalidTimeError)?;
+        require_gt!(expired_time, curtime, OptionError::InvalidTimeError);
+        let period_sec = i64::try_from(period)
+            .ok()
+            .and_then(|period| period.checked_mul(86400))
+            .ok_or(OptionError::InvalidTimeError)?;
+        let period_expiry = math::checked_add(curtime, period_sec)?;
+        require_gte!(
+            Self::EXPIRY_TOLERANCE_SEC,
+            math::checked_sub(expired_time, period_expiry)?.abs(),
+            OptionError::InvalidTimeError
+        );
+        Ok(())
+    }
+
+    /// Underlying delivered for `quantity` physically settled calls, in locked token decimals
+    pub fn get_delivery_amount(quantity: u64, locked_decimals: u8) -> Result<u64> {
+        math::checked_mul(quantity, math::checked_pow(10u64, locked_decimals as usize)?)
+    }
+
+    /// Strike value of `quantity` options in a token of `decimals`, a quote stablecoin at par
+    pub fn get_strike_value(&self, quantity: u64, decimals: u8) -> Result<u64> {
+        math::checked_decimal_mul(
+            self.strike_price,
+            self.strike_exponent,
+            quantity,
+            0, // quantity exponent
+            -(decimals as i32),
+        )
+    }
+
+    /// Strike a capped option stops paying at: `profit_cap` (usd per unit, USD_DECIMALS) above
+    /// the strike for calls and below it for puts, scaled like `strike_price`.
+    pub fn get_cap_strike(
+        strike_price: &OraclePrice,
+        is_call: bool,
+        profit_cap: u64,
+    ) -> Result<OraclePrice> {
+        let profit_cap = OraclePrice::new(profit_cap, -(Contract::USD_DECIMALS as i32))
+            .scale_to_exponent(strike_price.exponent)?;
+        let cap_strike = if is_call {
+            math::checked_add(strike_price.price, profit_cap.price)?
+        } else {
+            strike_price.price.saturating_sub(profit_cap.price)
+        };
+        require!(
+            profit_cap.price > 0 && cap_strike > 0,
+            OptionError::InvalidProfitCapError
+        );
+        Ok(OraclePrice::new(cap_strike, strike_price.exponent))
+    }
+
+    /// Max loss of writing `quantity` options in tokens of the locked custody: one underlying
+    /// per option for calls and the strike value in the quote stablecoin at par for puts.
+    /// Capped options lose at most `profit_cap` per option, worth profit_cap / cap strike of
+    /// the underlying for calls.
+    pub fn get_max_loss(
+        quantity: u64,
+        strike_price: &OraclePrice,
+        is_call: bool,
+        profit_cap: Option<u64>,
+        locked_decimals: u8,
+    ) -> Result<u64> {
+        let locked_exponent = -(locked_decimals as i32);
+        let Some(profit_cap) = profit_cap else {
+            return if is_call {
+                math::checked_decimal_mul(quantity, 0, 1, 0, locked_exponent)
+            } else {
+                math::checked_decimal_mul(
+                    strike_price.price,
+                    strike_price.exponent,
+                    quantity,
+                    0, // quantity exponent
+                    locked_exponent,
+                )
+            };
+        };
+
+        let cap_strike = OptionDetail::get_cap_strike(strike_price, is_call, profit_cap)?;
+        let max_profit_usd = math::checked_decimal_mul(
+            profit_cap,
+            -(Contract::USD_DECIMALS as i32),
+            quantity,
+            0, // quantity exponent
+            locked_exponent,
+        )?;
+        if is_call {
+            math::checked_decimal_div(
+                max_profit_usd,
+                locked_exponent,
+                cap_strike.price,
+                cap_strike.exponent,
+                locked_exponent,
+            )
+        } else {
+            Ok(max_profit_usd)
+        }
+    }
+
+    /// Clamps a payout in locked tokens to the profit cap of capped options.
+    pub fn cap_profit(&self, profit: u64) -> u64 {
+        match self.max_profit {
+            Some(max_profit) => profit.min(max_profit),
+            None => profit,
+        }
+    }
+
+    /// Profit of exercising at `oracle_price` paid out in the locked asset priced at
+    /// `locked_price` and clamped to the profit cap, 0 when the option is not in the money.
+    pub fn get_exercise_profit(
+        &self,
+        oracle_price: &OraclePrice,
+        locked_price: &OraclePrice,
+        custody_decimals: u8,
+        locked_decimals: u8,
+    ) -> Result<u64> {
+        if !self.is_in_the_money(oracle_price) {
+            return Ok(0);
+        }
+        // compare oracle and strike at the finer of both exponents
+        let exponent = std::cmp::min(oracle_price.exponent, self.strike_exponent);
+        let oracle_price = oracle_price.scale_to_exponent(exponent)?;
+        let strike_price = self.get_strike_price().scale_to_exponent(exponent)?;
+        let price_diff = if self.option_type == 0 {
+            math::checked_sub(oracle_price.price, strike_price.price)?
+        } else {
+            math::checked_sub(strike_price.price, oracle_price.price)?
+        };
+
+        // (price_diff * quantity) in usd, converted to locked tokens
+        let amount = math::checked_decimal_mul(
+            price_diff,
+            exponent,
+            self.quantity,
+            0, // quantity exponent
+            -(custody_decimals as i32), // target token decimals
+        )?;
+        locked_price.validate_positive()?;
+
+        let profit = math::checked_decimal_div(
+            amount,
+            -(custody_decimals as i32), // amount is already in target decimals
+            locked_price.price,
+            locked_price.exponent,
+            -(locked_decimals as i32),
+        )?;
+        Ok(self.cap_profit(profit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Custody;
+
+    const SOL_DECIMALS: u8 = 9;
+    const USDC_DECIMALS: u8 = 6;
+
+    fn strike(price: u64) -> OraclePrice {
+        OraclePrice::new(price * 100, -2)
+    }
+
+    #[test]
+    fn max_loss_of_calls_is_in_locked_decimals() {
+        // a 9 decimal underlying locks whole tokens whatever the 6 decimal pay token
+        assert_eq!(
+            OptionDetail::get_max_loss(3, &strike(150), true, None, SOL_DECIMALS).unwrap(),
+            3_000_000_000
+        );
+        assert_eq!(
+            OptionDetail::get_max_loss(3, &strike(150), true, None, USDC_DECIMALS).unwrap(),
+            3_000_000
+        );
+    }
+
+    fn usd(amount: u64) -> u64 {
+        amount * 10u64.pow(Contract::USD_DECIMALS as u32)
+    }
+
+    fn capped_call(quantity: u64, strike_price: u64, profit_cap: u64) -> OptionDetail {
+        OptionDetail {
+            quantity,
+            strike_price: strike(strike_price).price,
+            strike_exponent: strike(strike_price).exponent,
+            option_type: 0,
+            max_profit: Some(
+                OptionDetail::get_max_loss(
+                    quantity,
+                    &strike(strike_price),
+                    true,
+                    Some(usd(profit_cap)),
+                    SOL_DECIMALS,
+                )
+                .unwrap(),
+            ),
+            profit_cap: Some(usd(profit_cap)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn max_loss_of_puts_is_the_strike_value() {
+        assert_eq!(
+            OptionDetail::get_max_loss(3, &strike(150), false, None, USDC_DECIMALS).unwrap(),
+            450_000_000
+        );
+    }
+
+    #[test]
+    fn capped_collateral_matches_the_capped_max_loss() {
+        // a call capped 25 above a 100 strike loses at most 25 / 125 of the underlying
+        assert_eq!(
+            OptionDetail::get_max_loss(4, &strike(100), true, Some(usd(25)), SOL_DECIMALS)
+                .unwrap(),
+            800_000_000
+        );
+        // a put capped 25 below a 100 strike loses at most 25 of the quote
+        assert_eq!(
+            OptionDetail::get_max_loss(4, &strike(100), false, Some(usd(25)), USDC_DECIMALS)
+                .unwrap(),
+            usd(100)
+        );
+        assert!(OptionDetail::get_max_loss(4, &strike(100), false, Some(usd(100)), USDC_DECIMALS)
+            .is_err());
+    }
+
+    #[test]
+    fn capped_payouts_are_clamped_at_the_cap() {
+        let option_detail = capped_call(4, 100, 25);
+        let profit_at = |price: u64| {
+            let price = OraclePrice::new(price * 100_000_000, -8);
+            option_detail
+                .get_exercise_profit(&price, &price, SOL_DECIMALS, SOL_DECIMALS)
+                .unwrap()
+        };
+        // below the cap strike the payout is the intrinsic value, 10 * 4 / 110 SOL
+        assert_eq!(profit_at(110), 363_636_363);
+        // past it the payout stops at the locked max loss
+        assert_eq!(profit_at(125), 800_000_000);
+        assert_eq!(profit_at(200), 800_000_000);
+    }
+
+    #[test]
+    fn capped_premium_is_the_spread_to_the_cap_strike() {
+        let price = |k: u64, profit_cap: Option<u64>| {
+            OptionDetail::get_capped_option_price(
+                PricingModel::BlackScholes,
+                105.0,
+                &strike(k),
+                30.0 / 365.0,
+                true,
+                8000,
+                profit_cap,
+            )
+            .unwrap()
+        };
+        let capped = price(100, Some(usd(25)));
+        assert!((capped - (price(100, None) - price(125, None))).abs() < 1e-9);
+        assert!(capped > 0.0 && capped < price(100, None) && capped < 25.0);
+    }
+
+    #[test]
+    fn buybacks_are_converted_to_locked_tokens_in_fixed_point() {
+        // 2.5 usd per contract, 3 contracts
+        let sol = OraclePrice::new(150 * 100_000_000, -8);
+        let usdc = OraclePrice::new(100_000_000, -8);
+        assert_eq!(
+            OptionDetail::get_buyback_amount(2.5, 3, &sol, 9).unwrap(),
+            50_000_000
+        );
+        assert_eq!(
+            OptionDetail::get_buyback_amount(2.5, 3, &usdc, 6).unwrap(),
+            7_500_000
+        );
+        // below a micro usd per contract the model price rounds down to nothing
+        assert_eq!(
+            OptionDetail::get_buyback_amount(0.000_000_9, 1_000, &usdc, 6).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn physical_calls_deliver_the_quantity_against_the_strike() {
+        // 3 WSOL calls struck at 150 USDC
+        let option = OptionDetail {
+            strike_price: 150 * 100_000_000,
+            strike_exponent: -8,
+            quantity: 3,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            OptionDetail::get_delivery_amount(option.quantity, 9).unwrap(),
+            3_000_000_000
+        );
+        assert_eq!(option.get_strike_value(option.quantity, 6).unwrap(), 450_000_000);
+    }
+
+    fn uncapped(
+        option_type: u8,
+        quantity: u64,
+        strike_price: u64,
+        locked_decimals: u8,
+    ) -> OptionDetail {
+        OptionDetail {
+            quantity,
+            strike_price: strike(strike_price).price,
+            strike_exponent: strike(strike_price).exponent,
+            option_type,
+            amount: OptionDetail::get_max_loss(
+                quantity,
+                &strike(strike_price),
+                option_type == 0,
+                None,
+                locked_decimals,
+            )
+            .unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn expired_puts_pay_the_intrinsic_value_and_free_the_rest() {
+        // 2 puts struck at 150 lock 300 USDC
+        let option_detail = uncapped(1, 2, 150, USDC_DECIMALS);
+        let usdc = OraclePrice::new(100_000_000, -8);
+        let profit_at = |price: u64| {
+            option_detail
+                .get_exercise_profit(
+                    &OraclePrice::new(price * 100_000_000, -8),
+                    &usdc,
+                    SOL_DECIMALS,
+                    USDC_DECIMALS,
+                )
+                .unwrap()
+        };
+        assert_eq!(option_detail.amount, usd(300));
+        // out of the money the whole collateral goes back to the pool
+        assert_eq!(profit_at(160), 0);
+        // in the money the holder is owed the intrinsic value, the rest is unlocked
+        assert_eq!(profit_at(120), usd(60));
+        assert_eq!(option_detail.amount - profit_at(120), usd(240));
+
+        // the pool holds 1000 USDC, 300 of them locked: at expiry the 60 owed are queued as
+        // a claim, they leave token_owned once claim_option pays them
+        let pool_custody = Custody {
+            token_owned: usd(1_000),
+            token_locked: usd(300),
+            ..Default::default()
+        };
+        let mut custody = pool_custody.clone();
+        custody.unlock_funds(option_detail.amount).unwrap();
+        custody.add_claim(profit_at(120)).unwrap();
+        assert_eq!(custody.token_owned, usd(1_000));
+        assert_eq!(custody.get_withdrawable_amount().unwrap(), usd(940));
+        custody.pay_claim(profit_at(120)).unwrap();
+        assert_eq!(custody.token_owned, usd(940));
+        assert_eq!(custody.get_withdrawable_amount().unwrap(), usd(940));
+
+        // a cash exercise within the settlement cap pays the same 60 out at once
+        let mut custody = pool_custody;
+        custody.unlock_funds(option_detail.amount).unwrap();
+        assert!(custody.book_payout(profit_at(120), 1_700_000_000).unwrap());
+        assert_eq!(custody.token_owned, usd(940));
+        assert_eq!(custody.get_withdrawable_amount().unwrap(), usd(940));
+    }
+
+    #[test]
+    fn uncapped_payouts_stay_within_the_locked_collateral() {
+        // calls pay (price - strike) / price of the underlying, puts at most the strike value
+        let call = uncapped(0, 3, 100, SOL_DECIMALS);
+        for price in [101, 1_000, 1_000_000] {
+            let price = OraclePrice::new(price * 100_000_000, -8);
+            let profit = call
+                .get_exercise_profit(&price, &price, SOL_DECIMALS, SOL_DECIMALS)
+                .unwrap();
+            assert!(profit > 0 && profit < call.amount);
+        }
+
+        let put = uncapped(1, 3, 100, USDC_DECIMALS);
+        let usdc = OraclePrice::new(100_000_000, -8);
+        for price in [99 * 100_000_000, 1] {
+            let profit = put
+                .get_exercise_profit(
+                    &OraclePrice::new(price, -8),
+                    &usdc,
+                    SOL_DECIMALS,
+                    USDC_DECIMALS,
+                )
+                .unwrap();
+            assert!(profit > 0 && profit <= put.amount);
         }
     }
 }
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use super::Pool;
+
+/// Page of the book of live options of a pool. Pages are opened in order as the book grows
+/// and a write lists its option on any page with room, so a full page never blocks writes.
+#[account]
+#[derive(Default, Debug)]
+pub struct OptionBookPage {
+    pub pool: Pubkey,
+    pub index: u32,
+    pub options: Vec<Pubkey>, // OptionDetail accounts of live options, up to CAPACITY
+    pub bump: u8,
+}
+
+impl OptionBookPage {
+    pub const CAPACITY: usize = 64; // a multiple of Pool::OPEN_OPTIONS_PAGE_SIZE
+    pub const LEN: usize =
+        8 + std::mem::size_of::<OptionBookPage>() + Self::CAPACITY * std::mem::size_of::<Pubkey>();
+
+    /// Book page holding page `view_page` of get_open_options
+    pub fn get_book_page(view_page: u32) -> u32 {
+        view_page / (Self::CAPACITY / Pool::OPEN_OPTIONS_PAGE_SIZE) as u32
+    }
+
+    /// Options of page `view_page` of get_open_options, empty past the end of the page
+    pub fn get_view_page(&self, view_page: u32) -> &[Pubkey] {
+        let chunk = view_page as usize % (Self::CAPACITY / Pool::OPEN_OPTIONS_PAGE_SIZE);
+        self.options
+            .chunks(Pool::OPEN_OPTIONS_PAGE_SIZE)
+            .nth(chunk)
+            .unwrap_or_default()
+    }
+}
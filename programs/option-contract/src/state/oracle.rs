@@ -1,7 +1,33 @@
 use anchor_lang::prelude::*;
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
+use pyth_solana_receiver_sdk::price_update::{
+    get_feed_id_from_hex, PriceFeedMessage, PriceUpdateV2, VerificationLevel,
+};
 use core::cmp::Ordering;
-use crate::{errors::ContractError, math, state::Contract};
+use crate::{errors::{ContractError, OptionError}, math, state::Contract};
+
+#[derive(Copy, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
+pub enum OracleKind {
+    #[default]
+    Pyth, // PriceUpdateV2 accounts of the Pyth Receiver program
+    Switchboard, // pull feed accounts of the Switchboard On-Demand program
+}
+
+impl OracleKind {
+    // mainnet and devnet deployments of Switchboard On-Demand
+    pub const SWITCHBOARD_PROGRAM_IDS: [Pubkey; 2] = [
+        anchor_lang::pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv"),
+        anchor_lang::pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2"),
+    ];
+
+    /// Kind of an oracle account by its owner, anything not owned by Switchboard is read as Pyth
+    pub fn of(oracle_account: &AccountInfo) -> OracleKind {
+        if Self::SWITCHBOARD_PROGRAM_IDS.contains(oracle_account.owner) {
+            OracleKind::Switchboard
+        } else {
+            OracleKind::Pyth
+        }
+    }
+}
 
 #[derive(Copy, Clone, Eq, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
 pub struct OraclePrice {
@@ -30,10 +56,20 @@ impl PartialOrd for OraclePrice {
 
 #[allow(dead_code)]
 impl OraclePrice {
-    pub const MAX_PRICE_AGE_SEC: u64 = 6000; // 5 minutes - increased for more flexibility
+    pub const MAX_PRICE_AGE_SEC: u64 = 6000; // 100 minutes, loose limit of the unchecked loaders
+    pub const SETTLEMENT_WINDOW_SEC: i64 = 60; // accepted prints after option expiry
+    pub const SETTLEMENT_GRACE_SEC: i64 = 86400; // accepted prints after expiry for deferred settlement
+    pub const SETTLEMENT_EPSILON_SEC: i64 = 2; // prints this close to expiry settle without being the first
+    pub const DEFAULT_PUBLISH_TIME_SKEW_SEC: u64 = 30; // contract max_publish_time_skew_sec at initialize
+    pub const SETTLEMENT_MAX_AGE_SEC: i64 = 60; // max age of live prints used to settle expired options
     pub const ORACLE_MAX_PRICE: u64 = (1 << 28) - 1;
     pub const ORACLE_EXPONENT_SCALE: i32 = -9;
     pub const ORACLE_PRICE_SCALE: u64 = 1_000_000_000;
+    // PullFeedAccountData layout of Switchboard On-Demand, offsets include the discriminator
+    const SWITCHBOARD_FEED_HASH_OFFSET: usize = 2120;
+    const SWITCHBOARD_LAST_UPDATE_OFFSET: usize = 2216;
+    const SWITCHBOARD_RESULT_OFFSET: usize = 2264; // value: i128, then std_dev: i128
+    const SWITCHBOARD_DECIMALS: i32 = 18;
     
     pub fn new(price: u64, exponent: i32) -> Self {
         Self { price, exponent }
@@ -46,6 +82,22 @@ impl OraclePrice {
         }
     }
     
+    /// Price of a feed message, rejected when zero or negative as printed while a feed
+    /// initializes, which would make premiums and payouts meaningless
+    fn new_from_price_message(price_message: &PriceFeedMessage) -> Result<OraclePrice> {
+        let price = OraclePrice {
+            price: u64::try_from(price_message.price).unwrap_or_default(),
+            exponent: price_message.exponent,
+        };
+        price.validate_positive()?;
+        Ok(price)
+    }
+
+    pub fn validate_positive(&self) -> Result<()> {
+        require_gt!(self.price, 0, OptionError::InvalidPriceRequirementError);
+        Ok(())
+    }
+
     pub fn get_price(&self) -> f64 {
         let oracle_price = (self.price as f64) * 10f64.powi(self.exponent);
         oracle_price
@@ -61,6 +113,236 @@ impl OraclePrice {
         Self::get_pyth_price_from_update_account(oracle_account)
     }
 
+    /// Price of `oracle_account` published at most `max_age_sec` before `current_time`, with a
+    /// confidence interval of at most `max_conf_bps` of the price (0 skips the check).
+    pub fn new_from_oracle_checked(
+        oracle_account: &AccountInfo,
+        current_time: i64,
+        max_age_sec: u64,
+        max_conf_bps: u64,
+    ) -> Result<OraclePrice> {
+        let price_update = Self::load_price_update(oracle_account)?;
+        let price_message = &price_update.price_message;
+
+        let age = math::checked_sub(current_time, price_message.publish_time)?;
+        if age > max_age_sec as i64 {
+            msg!("Error: Oracle price is {} seconds old, max {}", age, max_age_sec);
+            return err!(ContractError::StaleOraclePrice);
+        }
+        let price = Self::new_from_price_message(price_message)?;
+        if max_conf_bps > 0
+            && math::checked_mul(price_message.conf as u128, Contract::BPS_POWER)?
+                > math::checked_mul(price.price as u128, max_conf_bps as u128)?
+        {
+            msg!("Error: Oracle confidence {} is too wide for price {}", price_message.conf, price.price);
+            return err!(ContractError::OraclePriceConfidenceError);
+        }
+
+        Ok(price)
+    }
+
+    /// Median price of the fresh and valid feeds among `oracle_accounts`, the mean of the two
+    /// middle prices for an even count. Feeds that fail to load or fail the age and confidence
+    /// limits of `new_from_oracle_checked` are skipped and at least `min_valid_feeds` of them
+    /// must remain.
+    pub fn new_from_oracles(
+        oracle_accounts: &[&AccountInfo],
+        min_valid_feeds: usize,
+        current_time: i64,
+        max_age_sec: u64,
+        max_conf_bps: u64,
+    ) -> Result<OraclePrice> {
+        let prices: Vec<OraclePrice> = oracle_accounts
+            .iter()
+            .filter_map(|oracle_account| {
+                match Self::new_from_oracle_checked(
+                    oracle_account,
+                    current_time,
+                    max_age_sec,
+                    max_conf_bps,
+                ) {
+                    Ok(price) => Some(price),
+                    Err(_) => {
+                        msg!("Skipping invalid oracle feed {}", oracle_account.key());
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self::get_median(&prices, min_valid_feeds)
+    }
+
+    /// Median of the settlement prints of several feeds of one asset, each given as an oracle
+    /// account and its settlement print as for `new_settlement_price`. Feeds without a valid
+    /// settlement print are skipped and at least `min_valid_feeds` of them must remain.
+    pub fn new_settlement_price_from_oracles(
+        feeds: &[(&AccountInfo, &AccountInfo)],
+        min_valid_feeds: usize,
+        expired_date: i64,
+        deferred: bool,
+    ) -> Result<OraclePrice> {
+        let prices: Vec<OraclePrice> = feeds
+            .iter()
+            .filter_map(|(oracle_account, settlement_account)| {
+                match Self::new_settlement_price(
+                    oracle_account,
+                    settlement_account,
+                    expired_date,
+                    deferred,
+                ) {
+                    Ok(price) => Some(price),
+                    Err(_) => {
+                        msg!(
+                            "Skipping invalid settlement print of {}",
+                            oracle_account.key()
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self::get_median(&prices, min_valid_feeds)
+    }
+
+    fn get_median(prices: &[OraclePrice], min_valid_feeds: usize) -> Result<OraclePrice> {
+        if prices.is_empty() || prices.len() < min_valid_feeds {
+            msg!("Error: {} valid oracle feeds, {} required", prices.len(), min_valid_feeds);
+            return err!(ContractError::InsufficientOracleFeeds);
+        }
+
+        // compare all feeds at the finest exponent
+        let exponent = prices.iter().map(|price| price.exponent).min().unwrap_or_default();
+        let mut scaled_prices = prices
+            .iter()
+            .map(|price| Ok(price.scale_to_exponent(exponent)?.price))
+            .collect::<Result<Vec<u64>>>()?;
+        scaled_prices.sort_unstable();
+
+        let mid = scaled_prices.len() / 2;
+        let price = if scaled_prices.len() % 2 == 0 {
+            math::checked_as_u64(math::checked_div(
+                math::checked_add(scaled_prices[mid - 1] as u128, scaled_prices[mid] as u128)?,
+                2,
+            )?)?
+        } else {
+            scaled_prices[mid]
+        };
+
+        Ok(OraclePrice { price, exponent })
+    }
+
+    /// Get the settlement price of an option expiring at `expired_date`.
+    /// `settlement_account` must carry a fully verified print of the same feed as
+    /// `oracle_account`, the first one published at or after expiry: its previous print
+    /// predates expiry, or it is within SETTLEMENT_EPSILON_SEC of expiry for feeds that don't
+    /// report their previous print. The keeper can't pick a favorable print among later ones,
+    /// so the result does not depend on when the transaction is submitted. `oracle_account`
+    /// itself can be passed as the settlement account while its latest print is that one.
+    /// The first print has to land within SETTLEMENT_WINDOW_SEC after expiry, or within
+    /// SETTLEMENT_GRACE_SEC for deferred settlements of options that expired during an oracle
+    /// outage, where it is the recovery print.
+    pub fn new_settlement_price(
+        oracle_account: &AccountInfo,
+        settlement_account: &AccountInfo,
+        expired_date: i64,
+        deferred: bool,
+    ) -> Result<OraclePrice> {
+        // Only accept price updates written by the program that feeds the custody oracle
+        // or posted through the Pyth Receiver program
+        require!(
+            settlement_account.owner == oracle_account.owner
+                || settlement_account.owner == &pyth_solana_receiver_sdk::ID,
+            ContractError::InvalidOracleAccount
+        );
+
+        let feed_update = Self::load_price_update(oracle_account)?;
+        let settlement_update = Self::load_price_update(settlement_account)?;
+        let price_message = &settlement_update.price_message;
+        require!(
+            price_message.feed_id == feed_update.price_message.feed_id,
+            ContractError::InvalidOracleAccount
+        );
+
+        require!(
+            settlement_update.verification_level == VerificationLevel::Full,
+            ContractError::InvalidSettlementPrice
+        );
+
+        // Reject prints from before expiry and late prints past the settlement window
+        let settlement_deadline = Self::get_settlement_deadline(expired_date, deferred)?;
+        require!(
+            price_message.publish_time >= expired_date
+                && price_message.publish_time <= settlement_deadline,
+            ContractError::InvalidSettlementPrice
+        );
+
+        // Only the first print at or after expiry settles
+        require!(
+            price_message.prev_publish_time < expired_date
+                || price_message.publish_time
+                    <= math::checked_add(expired_date, Self::SETTLEMENT_EPSILON_SEC)?,
+            ContractError::InvalidSettlementPrice
+        );
+        msg!("Settlement price: {}, exponent: {}, published {} seconds after expiry",
+             price_message.price, price_message.exponent,
+             price_message.publish_time - expired_date);
+
+        Self::new_from_price_message(price_message)
+    }
+
+    /// Live price used next to the settlement price of an expired option. The print has to be
+    /// published after expiry and at most SETTLEMENT_MAX_AGE_SEC before `current_time`, so a
+    /// keeper can't pick an old print that happens to be favorable.
+    pub fn new_settlement_leg_price(
+        oracle_account: &AccountInfo,
+        current_time: i64,
+        expired_date: i64,
+    ) -> Result<OraclePrice> {
+        let publish_time = Self::get_publish_time(oracle_account)?;
+        if publish_time < expired_date
+            || math::checked_sub(current_time, publish_time)? > Self::SETTLEMENT_MAX_AGE_SEC
+        {
+            msg!(
+                "Error: Print published at {} is stale for settlement at {} of expiry {}",
+                publish_time,
+                current_time,
+                expired_date
+            );
+            return err!(ContractError::StaleOraclePrice);
+        }
+        Self::new_from_oracle(oracle_account, current_time, false)
+    }
+
+    pub fn get_settlement_deadline(expired_date: i64, deferred: bool) -> Result<i64> {
+        let window = if deferred {
+            Self::SETTLEMENT_GRACE_SEC
+        } else {
+            Self::SETTLEMENT_WINDOW_SEC
+        };
+        math::checked_add(expired_date, window)
+    }
+
+    pub fn get_publish_time(oracle_account: &AccountInfo) -> Result<i64> {
+        Ok(Self::load_price_update(oracle_account)?.price_message.publish_time)
+    }
+
+    /// Rejects two feeds whose last prints are more than `max_skew_sec` apart, so a cross
+    /// price is never built from prices of different moments.
+    pub fn check_publish_time_skew(
+        oracle_account: &AccountInfo,
+        other_oracle_account: &AccountInfo,
+        max_skew_sec: u64,
+    ) -> Result<()> {
+        let publish_time = Self::get_publish_time(oracle_account)?;
+        let other_publish_time = Self::get_publish_time(other_oracle_account)?;
+        let skew = math::checked_sub(publish_time, other_publish_time)?.unsigned_abs();
+        if skew > max_skew_sec {
+            msg!("Error: Oracle publish times are {} seconds apart", skew);
+            return err!(ContractError::OraclePublishTimeSkew);
+        }
+        Ok(())
+    }
+
     /// Get price with explicit feed ID (recommended for production)
     pub fn new_from_oracle_with_feed_id(
         oracle_account: &AccountInfo,
@@ -103,18 +385,7 @@ impl OraclePrice {
         msg!("Pyth price: {}, exponent: {}, confidence: {}, age: {} seconds", 
              price_message.price, price_message.exponent, price_message.conf, age);
         
-        // Handle negative prices by taking absolute value
-        let price_value = if price_message.price < 0 {
-            msg!("Warning: Negative price detected, using absolute value");
-            (-price_message.price) as u64
-        } else {
-            price_message.price as u64
-        };
-        
-        Ok(OraclePrice {
-            price: price_value,
-            exponent: price_message.exponent,
-        })
+        Self::new_from_price_message(price_message)
     }
 
     // Rest of the methods remain the same
@@ -234,15 +505,16 @@ impl OraclePrice {
         }
     }
 
-    /// Main implementation - works with PriceUpdateV2 accounts
-    /// This method tries to auto-detect the feed ID from the price update
-    fn get_pyth_price_from_update_account(
-        oracle_account: &AccountInfo,
-    ) -> Result<OraclePrice> {
+    /// Deserialize a PriceUpdateV2 account without going through Account<T>, Switchboard feeds
+    /// are read into the same shape
+    fn load_price_update(oracle_account: &AccountInfo) -> Result<PriceUpdateV2> {
         require!(
             !Contract::is_empty_account(oracle_account)?,
             ContractError::InvalidOracleAccount
         );
+        if OracleKind::of(oracle_account) == OracleKind::Switchboard {
+            return Self::load_switchboard_feed(oracle_account);
+        }
 
         // Manual deserialization to avoid lifetime issues
         let data = oracle_account.try_borrow_data()
@@ -262,6 +534,65 @@ impl OraclePrice {
                 ContractError::InvalidOracleAccount
             })?;
 
+        Ok(price_update)
+    }
+
+    /// Latest result of a Switchboard pull feed as a PriceUpdateV2 message, the feed hash as its
+    /// feed id and the value and standard deviation normalized to ORACLE_EXPONENT_SCALE
+    fn load_switchboard_feed(oracle_account: &AccountInfo) -> Result<PriceUpdateV2> {
+        let data = oracle_account
+            .try_borrow_data()
+            .map_err(|_| ContractError::InvalidOracleAccount)?;
+        require!(
+            data.len() >= Self::SWITCHBOARD_RESULT_OFFSET + 32,
+            ContractError::InvalidOracleAccount
+        );
+        let read_i128 = |offset: usize| {
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&data[offset..offset + 16]);
+            i128::from_le_bytes(bytes)
+        };
+
+        let mut feed_id = [0u8; 32];
+        feed_id.copy_from_slice(
+            &data[Self::SWITCHBOARD_FEED_HASH_OFFSET..Self::SWITCHBOARD_FEED_HASH_OFFSET + 32],
+        );
+        let mut publish_time = [0u8; 8];
+        publish_time.copy_from_slice(
+            &data[Self::SWITCHBOARD_LAST_UPDATE_OFFSET..Self::SWITCHBOARD_LAST_UPDATE_OFFSET + 8],
+        );
+        let publish_time = i64::from_le_bytes(publish_time);
+
+        let scale = 10i128.pow((Self::SWITCHBOARD_DECIMALS + Self::ORACLE_EXPONENT_SCALE) as u32);
+        let price = i64::try_from(read_i128(Self::SWITCHBOARD_RESULT_OFFSET) / scale)
+            .map_err(|_| ContractError::InvalidOracleAccount)?;
+        let conf = u64::try_from(read_i128(Self::SWITCHBOARD_RESULT_OFFSET + 16) / scale)
+            .map_err(|_| ContractError::InvalidOracleAccount)?;
+
+        Ok(PriceUpdateV2 {
+            write_authority: *oracle_account.owner,
+            verification_level: VerificationLevel::Full,
+            price_message: PriceFeedMessage {
+                feed_id,
+                price,
+                conf,
+                exponent: Self::ORACLE_EXPONENT_SCALE,
+                publish_time,
+                prev_publish_time: publish_time,
+                ema_price: price,
+                ema_conf: conf,
+            },
+            posted_slot: 0,
+        })
+    }
+
+    /// Main implementation - works with PriceUpdateV2 accounts
+    /// This method tries to auto-detect the feed ID from the price update
+    fn get_pyth_price_from_update_account(
+        oracle_account: &AccountInfo,
+    ) -> Result<OraclePrice> {
+        let price_update = Self::load_price_update(oracle_account)?;
+
         let clock = Clock::get()?;
         
         // Extract the feed ID from the price message
@@ -281,18 +612,7 @@ impl OraclePrice {
              price_message.price, price_message.exponent, price_message.conf,
              age);
         
-        // Handle negative prices by taking absolute value
-        let price_value = if price_message.price < 0 {
-            msg!("Warning: Negative price detected, using absolute value");
-            (-price_message.price) as u64
-        } else {
-            price_message.price as u64
-        };
-        
-        Ok(OraclePrice {
-            price: price_value,
-            exponent: price_message.exponent,
-        })
+        Self::new_from_price_message(price_message)
     }
 
     /// Better implementation with explicit feed_id string
@@ -351,18 +671,7 @@ impl OraclePrice {
              feed_id_hex, price_message.price, price_message.exponent, price_message.conf,
              age);
         
-        // Handle negative prices by taking absolute value
-        let price_value = if price_message.price < 0 {
-            msg!("Warning: Negative price detected, using absolute value");
-            (-price_message.price) as u64
-        } else {
-            price_message.price as u64
-        };
-        
-        Ok(OraclePrice {
-            price: price_value,
-            exponent: price_message.exponent,
-        })
+        Self::new_from_price_message(price_message)
     }
 }
 
@@ -385,4 +694,155 @@ impl FeedId {
     
     /// USDT/USD feed ID
     pub const USDT_USD: &'static str = "0x2b89b9dc8fdf9f34709a5b106b472f0f39bb6ca8ce04b0fd7f2e971688e2e53b";
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    // PriceUpdateV2 account of the Pyth Receiver program
+    struct Feed {
+        key: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
+
+    impl Feed {
+        fn new(price: i64, publish_time: i64, verification_level: VerificationLevel) -> Self {
+            Feed::with_previous(price, publish_time, publish_time - 1, verification_level)
+        }
+
+        fn with_previous(
+            price: i64,
+            publish_time: i64,
+            prev_publish_time: i64,
+            verification_level: VerificationLevel,
+        ) -> Self {
+            let price_update = PriceUpdateV2 {
+                write_authority: Pubkey::default(),
+                verification_level,
+                price_message: PriceFeedMessage {
+                    feed_id: [1; 32],
+                    price,
+                    conf: 0,
+                    exponent: -8,
+                    publish_time,
+                    prev_publish_time,
+                    ema_price: price,
+                    ema_conf: 0,
+                },
+                posted_slot: 0,
+            };
+            let mut data = vec![0; 8];
+            price_update.serialize(&mut data).unwrap();
+            Feed {
+                key: Pubkey::new_unique(),
+                lamports: 1,
+                data,
+            }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                false,
+                &mut self.lamports,
+                &mut self.data,
+                &pyth_solana_receiver_sdk::ID,
+                false,
+                0,
+            )
+        }
+    }
+
+    fn usd(price: i64) -> i64 {
+        price * 100_000_000
+    }
+
+    #[test]
+    fn median_of_oracles_skips_stale_feeds() {
+        let mut fresh = Feed::new(usd(100), NOW - 10, VerificationLevel::Full);
+        let mut other_fresh = Feed::new(usd(104), NOW - 5, VerificationLevel::Full);
+        let mut stale = Feed::new(usd(50), NOW - 120, VerificationLevel::Full);
+        let (fresh, other_fresh, stale) = (fresh.info(), other_fresh.info(), stale.info());
+
+        // median of the two fresh feeds, the stale one is past the 60 seconds max age
+        let price =
+            OraclePrice::new_from_oracles(&[&fresh, &stale, &other_fresh], 2, NOW, 60, 0).unwrap();
+        assert_eq!(price, OraclePrice::new(usd(102) as u64, -8));
+
+        // within the age limit the stale feed counts again
+        let price =
+            OraclePrice::new_from_oracles(&[&fresh, &stale, &other_fresh], 3, NOW, 600, 0).unwrap();
+        assert_eq!(price, OraclePrice::new(usd(100) as u64, -8));
+
+        assert!(OraclePrice::new_from_oracles(&[&fresh, &stale, &other_fresh], 3, NOW, 60, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn settlement_takes_the_first_verified_print_after_expiry() {
+        let expiry = NOW;
+        let mut oracle = Feed::new(usd(100), expiry - 1, VerificationLevel::Full);
+        let mut first = Feed::with_previous(usd(100), expiry + 3, expiry - 1, VerificationLevel::Full);
+        let mut partial = Feed::with_previous(
+            usd(100),
+            expiry + 3,
+            expiry - 1,
+            VerificationLevel::Partial { num_signatures: 3 },
+        );
+        // a later print the keeper may prefer, its previous print is already past expiry
+        let mut later = Feed::with_previous(usd(90), expiry + 30, expiry + 3, VerificationLevel::Full);
+        let mut early = Feed::new(usd(100), expiry - 1, VerificationLevel::Full);
+        let oracle = oracle.info();
+        let settle = |feed: &mut Feed| {
+            OraclePrice::new_settlement_price(&oracle, &feed.info(), expiry, false)
+        };
+
+        assert_eq!(settle(&mut first).unwrap(), OraclePrice::new(usd(100) as u64, -8));
+        assert!(settle(&mut partial).is_err());
+        assert!(settle(&mut later).is_err());
+        assert!(settle(&mut early).is_err());
+    }
+
+    #[test]
+    fn settlement_median_skips_feeds_without_a_first_print() {
+        let expiry = NOW;
+        let mut oracle = Feed::new(usd(100), expiry - 1, VerificationLevel::Full);
+        let mut print =
+            Feed::with_previous(usd(100), expiry + 3, expiry - 1, VerificationLevel::Full);
+        let mut backup = Feed::new(usd(104), expiry - 1, VerificationLevel::Full);
+        let mut backup_print =
+            Feed::with_previous(usd(104), expiry + 5, expiry - 1, VerificationLevel::Full);
+        // the other backup only offers a later print
+        let mut other_backup = Feed::new(usd(90), expiry - 1, VerificationLevel::Full);
+        let mut other_backup_print =
+            Feed::with_previous(usd(90), expiry + 30, expiry + 3, VerificationLevel::Full);
+        let (oracle, print) = (oracle.info(), print.info());
+        let (backup, backup_print) = (backup.info(), backup_print.info());
+        let (other_backup, other_backup_print) = (other_backup.info(), other_backup_print.info());
+        let feeds = [
+            (&oracle, &print),
+            (&backup, &backup_print),
+            (&other_backup, &other_backup_print),
+        ];
+
+        let price =
+            OraclePrice::new_settlement_price_from_oracles(&feeds, 2, expiry, false).unwrap();
+        assert_eq!(price, OraclePrice::new(usd(102) as u64, -8));
+        assert!(OraclePrice::new_settlement_price_from_oracles(&feeds, 3, expiry, false).is_err());
+    }
+
+    #[test]
+    fn publish_time_skew_is_bounded_by_the_contract_limit() {
+        let mut feed = Feed::new(usd(100), NOW, VerificationLevel::Full);
+        let mut other_feed = Feed::new(usd(1), NOW - 20, VerificationLevel::Full);
+        let (feed, other_feed) = (feed.info(), other_feed.info());
+
+        assert!(OraclePrice::check_publish_time_skew(&feed, &other_feed, 30).is_ok());
+        assert!(OraclePrice::check_publish_time_skew(&other_feed, &feed, 20).is_ok());
+        assert!(OraclePrice::check_publish_time_skew(&feed, &other_feed, 10).is_err());
+    }
+}
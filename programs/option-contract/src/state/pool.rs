@@ -2,9 +2,12 @@ use std::cmp::Ordering;
 
 use anchor_lang::prelude::*;
 
-use crate::{errors::PoolError, math};
+use crate::{
+    errors::{OptionError, PoolError},
+    math,
+};
 
-use super::{Contract, Custody, OraclePrice};
+use super::{Contract, Custody, OptionBookPage, OraclePrice};
 
 #[derive(Copy, Clone, PartialEq, AnchorSerialize, AnchorDeserialize, Default, Debug)]
 pub struct TokenRatios {
@@ -19,13 +22,228 @@ pub struct Pool {
     pub name: String,
     pub custodies: Vec<Pubkey>,
     pub ratios: Vec<TokenRatios>,
+    pub allowed_premium_mints: Vec<Pubkey>, // premium currencies of the pool, empty = any custody
+    pub keepers: Vec<Pubkey>, // signers allowed to auto exercise, empty = anyone
+    pub quote_custody: Pubkey, // stablecoin custody securing puts, default = any custody
     pub aum_usd: u128,
+    pub cumulative_premium_usd: u128, // premiums collected from option writes
+    pub cumulative_payout_usd: u128,  // profits paid out on exercise
+    pub max_decimals_diff: u8, // max decimals gap between tokens settled against each other
+    pub risk_group_caps: [u64; 8], // usd locked across the custodies of risk group i + 1, 0 = no cap
+    pub max_deposit_per_lp_usd: u64, // cumulative deposits per liquidity provider, 0 = no cap
+    pub max_total_deposits_usd: u64, // max assets under management, 0 = no cap
+    pub max_utilization_bps: u16, // max locked share of each custody, 0 = no cap
+    pub open_options: u64, // live options listed in the option book
+    pub option_book_pages: u32, // pages of the option book, see OptionBookPage
     pub bump: u8,
     pub lp_token_bump: u8,
 }
 
 impl Pool {
     pub const LEN: usize = 8 + 64 + std::mem::size_of::<Pool>();
+    // e.g. 6 decimals stablecoins against 18 decimals tokens
+    pub const DEFAULT_MAX_DECIMALS_DIFF: u8 = 12;
+    pub const MAX_RISK_GROUPS: u8 = 8;
+    pub const OPEN_OPTIONS_PAGE_SIZE: usize = 8; // summaries per page, fits the return data
+    pub const MIN_INITIAL_LP_AMOUNT: u64 = 1_000_000_000; // LP tokens of the first deposit, 1000 usd
+
+    /// Conversions between tokens with a larger decimals gap lose too much precision
+    pub fn check_decimals_diff(&self, decimals: u8, other_decimals: u8) -> Result<()> {
+        require_gte!(
+            self.max_decimals_diff,
+            decimals.abs_diff(other_decimals),
+            PoolError::DecimalsDiffTooLargeError
+        );
+        Ok(())
+    }
+
+    /// Checks locking `amount` more keeps the custody within the utilization cap of the pool,
+    /// so some liquidity always stays free to pay out claims.
+    pub fn check_utilization(&self, custody: &Custody, amount: u64) -> Result<()> {
+        if self.max_utilization_bps == 0 {
+            return Ok(());
+        }
+        require_gt!(custody.token_owned, 0, OptionError::InvalidPoolBalanceError);
+        let utilization_bps = math::checked_div(
+            math::checked_mul(
+                math::checked_add(custody.token_locked, amount)? as u128,
+                Contract::BPS_POWER,
+            )?,
+            custody.token_owned as u128,
+        )?;
+        require_gte!(
+            self.max_utilization_bps as u128,
+            utilization_bps,
+            OptionError::InvalidPoolBalanceError
+        );
+        Ok(())
+    }
+
+    pub fn record_premium(&mut self, amount_usd: u64) -> Result<()> {
+        self.cumulative_premium_usd =
+            math::checked_add(self.cumulative_premium_usd, amount_usd as u128)?;
+        Ok(())
+    }
+
+    pub fn record_payout(&mut self, amount_usd: u64) -> Result<()> {
+        self.cumulative_payout_usd =
+            math::checked_add(self.cumulative_payout_usd, amount_usd as u128)?;
+        Ok(())
+    }
+
+    // realized pnl of the pool: premiums collected minus payouts made
+    pub fn get_realized_pnl_usd(&self) -> Result<i128> {
+        math::checked_sub(
+            math::checked_as_i128(self.cumulative_premium_usd)?,
+            math::checked_as_i128(self.cumulative_payout_usd)?,
+        )
+    }
+
+    pub fn is_premium_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.allowed_premium_mints.is_empty() || self.allowed_premium_mints.contains(mint)
+    }
+
+    pub fn is_keeper_allowed(&self, keeper: &Pubkey) -> bool {
+        self.keepers.is_empty() || self.keepers.contains(keeper)
+    }
+
+    pub fn get_risk_group_cap(&self, risk_group: u8) -> u64 {
+        if risk_group == 0 {
+            0
+        } else {
+            self.risk_group_caps[(risk_group - 1) as usize]
+        }
+    }
+
+    /// Usd value locked across the custodies sharing the risk group of `locked_custody`, which is
+    /// taken as updated in memory. Accounts are laid out as for the AUM: custodies, then oracles.
+    pub fn get_risk_group_locked_usd<'info>(
+        &self,
+        contract: &Contract,
+        locked_custody_key: &Pubkey,
+        locked_custody: &Custody,
+        accounts: &'info [AccountInfo<'info>],
+        curtime: i64,
+    ) -> Result<u128> {
+        let mut locked_usd: u128 = 0;
+        for (idx, &custody_key) in self.custodies.iter().enumerate() {
+            let oracle_idx = idx + self.custodies.len();
+            if oracle_idx >= accounts.len() {
+                return Err(ProgramError::NotEnoughAccountKeys.into());
+            }
+            require_keys_eq!(accounts[idx].key(), custody_key);
+            let loaded_custody;
+            let custody: &Custody = if custody_key == *locked_custody_key {
+                locked_custody
+            } else {
+                loaded_custody = Account::<Custody>::try_from(&accounts[idx])?;
+                &loaded_custody
+            };
+            if custody.risk_group != locked_custody.risk_group {
+                continue;
+            }
+
+            require_keys_eq!(accounts[oracle_idx].key(), custody.oracle);
+            let token_price = contract.get_oracle_price(&accounts[oracle_idx], curtime)?;
+            locked_usd = math::checked_add(
+                locked_usd,
+                token_price.get_asset_amount_usd(custody.token_locked, custody.decimals)? as u128,
+            )?;
+        }
+
+        Ok(locked_usd)
+    }
+
+    /// Room left under the total deposit cap for a pool worth `aum_usd`, None when uncapped
+    pub fn get_deposit_capacity_usd(&self, aum_usd: u128) -> Option<u128> {
+        if self.max_total_deposits_usd == 0 {
+            None
+        } else {
+            Some((self.max_total_deposits_usd as u128).saturating_sub(aum_usd))
+        }
+    }
+
+    /// LP tokens minted for a deposit worth `amount_usd` into a pool worth `pool_amount_usd`.
+    /// Shares are priced on the pool value, the first deposit mints them 1:1 with usd. It has
+    /// to be sizable, otherwise premiums paid into a dust supply inflate the share price until
+    /// later deposits round down to nothing.
+    pub fn get_lp_amount(amount_usd: u64, lp_supply: u64, pool_amount_usd: u128) -> Result<u64> {
+        if lp_supply == 0 {
+            require_gte!(
+                amount_usd,
+                Self::MIN_INITIAL_LP_AMOUNT,
+                PoolError::InitialDepositTooSmallError
+            );
+            return Ok(amount_usd);
+        }
+        math::checked_as_u64(math::checked_div(
+            math::checked_mul(amount_usd as u128, lp_supply as u128)?,
+            pool_amount_usd,
+        )?)
+    }
+
+    /// Pro-rata share of the pool value, premiums included, redeemed by `lp_amount` LP tokens
+    pub fn get_lp_value_usd(lp_amount: u64, lp_supply: u64, pool_amount_usd: u128) -> Result<u64> {
+        math::checked_as_u64(math::checked_div(
+            math::checked_mul(pool_amount_usd, lp_amount as u128)?,
+            lp_supply as u128,
+        )?)
+    }
+
+    /// Lists `option` on `page` of the option book, a new page has to be the next one of the
+    /// pool and is opened for it
+    pub fn add_open_option(
+        &mut self,
+        pool_key: Pubkey,
+        page: &mut OptionBookPage,
+        page_index: u32,
+        page_bump: u8,
+        option: Pubkey,
+    ) -> Result<()> {
+        if page.pool == Pubkey::default() {
+            require_eq!(
+                page_index,
+                self.option_book_pages,
+                PoolError::InvalidPoolState
+            );
+            page.pool = pool_key;
+            page.index = page_index;
+            page.bump = page_bump;
+            self.option_book_pages = math::checked_add(self.option_book_pages, 1)?;
+        }
+        require_gt!(
+            OptionBookPage::CAPACITY,
+            page.options.len(),
+            PoolError::OptionBookPageFullError
+        );
+        page.options.push(option);
+        self.open_options = math::checked_add(self.open_options, 1)?;
+        Ok(())
+    }
+
+    pub fn remove_open_option(&mut self, page: &mut OptionBookPage, option: &Pubkey) {
+        // options written before the book was kept are not listed
+        if let Some(idx) = page.options.iter().position(|k| k == option) {
+            page.options.swap_remove(idx);
+            self.open_options = self.open_options.saturating_sub(1);
+        }
+    }
+
+    /// Lists `new_option` in place of `old_option` when it takes over its position
+    pub fn replace_open_option(
+        &mut self,
+        page: &mut OptionBookPage,
+        old_option: &Pubkey,
+        new_option: Pubkey,
+    ) {
+        if let Some(listed) = page.options.iter_mut().find(|k| *k == old_option) {
+            *listed = new_option;
+        }
+    }
+
+    pub fn is_put_collateral(&self, locked_custody: &Pubkey) -> bool {
+        self.quote_custody == Pubkey::default() || self.quote_custody == *locked_custody
+    }
 
     pub fn get_token_id(&self, custody: &Pubkey) -> Result<usize> {
         self.custodies
@@ -146,7 +364,7 @@ impl Pool {
 
             let token_price = OraclePrice::new_from_oracle(&accounts[oracle_idx], curtime, false)?;
             let token_amount_usd =
-                token_price.get_asset_amount_usd(custody.token_owned, custody.decimals)?;
+                token_price.get_asset_amount_usd(custody.get_lp_owned_amount()?, custody.decimals)?;
             msg!("token_amount_usd: {}", token_amount_usd);
             msg!("token_price: {}", token_price.price);
             msg!("custody.token_owned: {}", custody.token_owned);
@@ -310,3 +528,80 @@ impl Pool {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_option_book_pages_leave_writes_to_the_next_page() {
+        let mut pool = Pool::default();
+        let pool_key = Pubkey::new_unique();
+        let (mut first, mut second) = (OptionBookPage::default(), OptionBookPage::default());
+        let capacity = OptionBookPage::CAPACITY as u64;
+
+        // pages are opened in order
+        assert!(pool
+            .add_open_option(pool_key, &mut second, 1, 255, Pubkey::new_unique())
+            .is_err());
+        for _ in 0..capacity {
+            pool.add_open_option(pool_key, &mut first, 0, 255, Pubkey::new_unique())
+                .unwrap();
+        }
+        assert!(pool
+            .add_open_option(pool_key, &mut first, 0, 255, Pubkey::new_unique())
+            .is_err());
+        let written = Pubkey::new_unique();
+        pool.add_open_option(pool_key, &mut second, 1, 255, written)
+            .unwrap();
+        assert_eq!((second.pool, second.index), (pool_key, 1));
+        assert_eq!(
+            (pool.option_book_pages, pool.open_options),
+            (2, capacity + 1)
+        );
+
+        // settling frees a slot on the page of the option, a roll takes over its slot
+        let settled = first.options[0];
+        pool.remove_open_option(&mut first, &settled);
+        assert_eq!(pool.open_options, capacity);
+        pool.add_open_option(pool_key, &mut first, 0, 255, Pubkey::new_unique())
+            .unwrap();
+        let rolled = Pubkey::new_unique();
+        pool.replace_open_option(&mut second, &written, rolled);
+        assert_eq!(second.options, vec![rolled]);
+
+        // pages of the view are read from the book page holding them
+        assert_eq!(OptionBookPage::get_book_page(9), 1);
+        assert_eq!(first.get_view_page(1), &first.options[8..16]);
+        assert_eq!(second.get_view_page(8), &[rolled]);
+        assert!(second.get_view_page(9).is_empty());
+    }
+
+    #[test]
+    fn lp_shares_pay_out_premiums_pro_rata() {
+        let usd = |amount: u64| amount * 1_000_000;
+
+        // the first deposit has to clear the bootstrap minimum
+        assert!(Pool::get_lp_amount(usd(999), 0, 0).is_err());
+
+        // A bootstraps the pool with 1000 usd, premiums add 100 usd before B deposits 1100 usd
+        let a_shares = Pool::get_lp_amount(usd(1_000), 0, 0).unwrap();
+        let mut supply = a_shares;
+        let mut pool_usd = (usd(1_000) + usd(100)) as u128;
+        let b_shares = Pool::get_lp_amount(usd(1_100), supply, pool_usd).unwrap();
+        supply += b_shares;
+        pool_usd += usd(1_100) as u128;
+        assert_eq!(a_shares, b_shares);
+
+        // another 200 usd of premiums are split evenly
+        pool_usd += usd(200) as u128;
+        let a_value = Pool::get_lp_value_usd(a_shares, supply, pool_usd).unwrap();
+        assert_eq!(a_value, usd(1_200));
+        supply -= a_shares;
+        pool_usd -= a_value as u128;
+        assert_eq!(
+            Pool::get_lp_value_usd(b_shares, supply, pool_usd).unwrap(),
+            usd(1_200)
+        );
+    }
+}
@@ -1,11 +1,57 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::OptionError;
+
 #[account]
 pub struct User {
     pub option_index: u64,
     pub bump: u8,
+    pub exercise_delegate: Option<Pubkey>, // may exercise options on behalf of the owner
+    pub delegate_payout: bool, // delegate may have the payout sent to its own token account
+    pub fee_exempt: bool, // in-house accounts that don't pay settlement fees
+    pub open_options: u64, // valid options of the user across pools
 }
 
 impl User {
-    pub const LEN: usize = 8 * 1 + 1 + 8;
+    pub const LEN: usize = 8 * 1 + 1 + 8 + (1 + 32) + 1 + 1 + 8;
+
+    pub fn next_option_index(&self) -> Result<u64> {
+        self.option_index
+            .checked_add(1)
+            .ok_or_else(|| OptionError::InvalidOptionIndexError.into())
+    }
+
+    /// Counts a new option of the user against `max_open_options`, 0 = unlimited
+    pub fn add_open_option(&mut self, max_open_options: u64) -> Result<()> {
+        self.open_options = self
+            .open_options
+            .checked_add(1)
+            .ok_or(OptionError::OpenInterestLimitError)?;
+        require!(
+            max_open_options == 0 || self.open_options <= max_open_options,
+            OptionError::OpenInterestLimitError
+        );
+        Ok(())
+    }
+
+    // options written before the counter existed were never counted
+    pub fn remove_open_option(&mut self) {
+        self.open_options = self.open_options.saturating_sub(1);
+    }
+
+    pub fn can_exercise(&self, owner: &Pubkey, signer: &Pubkey) -> bool {
+        signer == owner || self.exercise_delegate == Some(*signer)
+    }
+
+    /// Allowed signer / payout account combinations of an exercise:
+    /// - the owner signs, the payout goes to a token account of the owner
+    /// - the delegate signs, the payout goes to a token account of the owner
+    /// - the delegate signs, the payout goes to a token account of the delegate,
+    ///   only when the owner enabled delegate_payout
+    pub fn can_receive_payout(&self, owner: &Pubkey, signer: &Pubkey, recipient: &Pubkey) -> bool {
+        if !self.can_exercise(owner, signer) {
+            return false;
+        }
+        recipient == owner || (self.delegate_payout && signer != owner && recipient == signer)
+    }
 }
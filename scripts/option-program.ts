@@ -47,6 +47,9 @@ const WSOL_ORACLE = new PublicKey(
 const USDC_ORACLE = new PublicKey(
   "Dpw1EAVrSB1ibxiDQyTAW6Zip3J4Btk2x4SgApQCeFbX"
 );
+// strike prices are stored as integers scaled by 10^STRIKE_EXPONENT
+const STRIKE_EXPONENT = -8;
+const VOLATILITY_BPS = 5000; // annualized Black-Scholes volatility of new custodies
 
 const USDC_amount = 200_000_000_000_000;
 const WSOL_amount = 200_000_000_000_000_000;
@@ -243,7 +246,13 @@ const init = async () => {
   console.log("Initializing program:", await program.programId.toBase58());
 
   const tx = await program.methods
-    .initialize()
+    .initialize({
+      minSignatures: 1,
+      maxPriceAgeSec: new anchor.BN(60),
+      maxPriceConfBps: new anchor.BN(200),
+      feeBps: 0,
+      feeRecipient: wallet.publicKey,
+    })
     .accounts({
       signer: wallet.publicKey,
     })
@@ -379,6 +388,9 @@ const addCustodies = async (_poolName: string) => {
   const addCustody_WSOL_Tx = await program.methods
     .addCustody({
       oracle: WSOL_ORACLE,
+      oracleKind: { pyth: {} },
+      strikeExponent: STRIKE_EXPONENT,
+      volatilityBps: VOLATILITY_BPS,
       poolName: poolData.name,
     })
     .accounts({
@@ -426,6 +438,9 @@ const addCustodies = async (_poolName: string) => {
   const addCustody_USDC_Tx = await program.methods
     .addCustody({
       oracle: USDC_ORACLE,
+      oracleKind: { pyth: {} },
+      strikeExponent: STRIKE_EXPONENT,
+      volatilityBps: VOLATILITY_BPS,
       poolName: poolData.name,
     })
     .accounts({
@@ -499,6 +514,7 @@ const addUSDCLiquidity = async (_poolName: string) => {
       amountIn: new anchor.BN(1_000_000_000_000),
       minLpAmountOut: new anchor.BN(100_000),
       poolName: _poolName,
+      wrapSol: false,
     })
     .accountsPartial({
       owner: wallet.publicKey,
@@ -507,6 +523,7 @@ const addUSDCLiquidity = async (_poolName: string) => {
       transferAuthority: transferAuthority,
       custodyMint: USDCMint,
       custodyOracleAccount: USDC_ORACLE,
+      custodyTokenProgram: TOKEN_PROGRAM_ID,
     })
     .remainingAccounts(remainingAccounts)
     .signers([wallet.payer])
@@ -578,12 +595,14 @@ const addWSOLLiquidity = async (_poolName: string) => {
       amountIn: new anchor.BN(100_000_000_000_000),
       minLpAmountOut: new anchor.BN(100_000),
       poolName: _poolName,
+      wrapSol: false,
     })
     .accounts({
       owner: wallet.publicKey,
       fundingAccount: fundingAccount,
       custodyMint: WSOLMint,
       custodyOracleAccount: WSOL_ORACLE,
+      custodyTokenProgram: TOKEN_PROGRAM_ID,
     })
     .remainingAccounts(remainingAccounts)
     .signers([wallet.payer])
@@ -671,12 +690,20 @@ const openOption_Call_USDC = async (
   const tx = await program.methods
     .openOption({
       amount: new anchor.BN(_amount),
-      strike: _strike,
+      contracts: new anchor.BN(0),
+      strike: new anchor.BN(Math.round(_strike * 10 ** -STRIKE_EXPONENT)),
       expiredTime: new anchor.BN(
         Math.floor(Date.now() / 1000) + 86400 * _period
       ),
       period: new anchor.BN(_period),
       poolName: _poolName,
+      premiumSplitBps: new anchor.BN(0),
+      profitCap: null,
+      allowPartial: false,
+      isAmerican: true,
+      settlement: { cash: {} },
+      maxPremium: new anchor.BN(0),
+      minPremium: new anchor.BN(0),
     })
     .accountsPartial({
       owner: wallet.publicKey,
@@ -686,6 +713,7 @@ const openOption_Call_USDC = async (
       custodyOracleAccount: WSOL_ORACLE,
       payCustodyOracleAccount: USDC_ORACLE,
       lockedCustodyMint: WSOLMint,
+      lockedCustodyOracleAccount: WSOL_ORACLE,
       optionDetail: optionDetail,
       pool: poolPDA,
       custody: wsolCustody,
@@ -755,12 +783,20 @@ const openOption_Call = async (
   const tx = await program.methods
     .openOption({
       amount: new anchor.BN(_amount),
-      strike: _strike,
+      contracts: new anchor.BN(0),
+      strike: new anchor.BN(Math.round(_strike * 10 ** -STRIKE_EXPONENT)),
       expiredTime: new anchor.BN(
         Math.floor(Date.now() / 1000) + /* 86400 */ 1 * _period
       ),
       period: new anchor.BN(_period),
       poolName: _poolName,
+      premiumSplitBps: new anchor.BN(0),
+      profitCap: null,
+      allowPartial: false,
+      isAmerican: true,
+      settlement: { cash: {} },
+      maxPremium: new anchor.BN(0),
+      minPremium: new anchor.BN(0),
     })
     .accountsPartial({
       owner: wallet.publicKey,
@@ -770,6 +806,7 @@ const openOption_Call = async (
       custodyOracleAccount: WSOL_ORACLE,
       payCustodyOracleAccount: WSOL_ORACLE,
       lockedCustodyMint: WSOLMint,
+      lockedCustodyOracleAccount: WSOL_ORACLE,
       optionDetail: optionDetail,
       pool: poolPDA,
       custody: wsolCustody,
@@ -989,6 +1026,8 @@ const exerciseOption_Call = async (_poolName: string, _index: number) => {
     program.programId
   );
 
+  const optionData = await program.account.optionDetail.fetch(optionDetail);
+
   console.log("🚀 Exercising option...");
   console.log("  Pool:", _poolName);
   console.log("  Index:", _index);
@@ -1001,9 +1040,12 @@ const exerciseOption_Call = async (_poolName: string, _index: number) => {
       .exerciseOption({
         optionIndex: new anchor.BN(_index),
         poolName: _poolName,
+        exerciseAmount: optionData.amount,
+        minTokensOut: new anchor.BN(0),
       })
       .accountsPartial({
         owner: wallet.publicKey,
+        authority: wallet.publicKey,
         fundingAccount: fundingAccount,
         transferAuthority: transferAuthority,
         contract: contract,
@@ -1129,6 +1171,7 @@ const removeLiquidity = async (
       lpAmountIn: new anchor.BN(lp_amount),
       minAmountOut: new anchor.BN(min_amount_out),
       poolName: _poolName,
+      unwrapOnWithdraw: false,
     })
     .accountsPartial({
       owner: wallet.publicKey,
@@ -1143,6 +1186,7 @@ const removeLiquidity = async (
       lpTokenMint: lpTokenMint,
       custodyMint: asset,
       tokenProgram: TOKEN_PROGRAM_ID,
+      custodyTokenProgram: TOKEN_PROGRAM_ID,
     })
     .remainingAccounts(remainingAccounts)
     .signers([wallet.payer])
@@ -164,10 +164,13 @@ describe("Exercise Option Test - Based on Rust Code", () => {
         .exerciseOption({
           optionIndex: new anchor.BN(optionIndex),
           poolName: poolName,
+          exerciseAmount: initialOptionData.amount,
+          minTokensOut: new anchor.BN(0),
         })
         .accounts({
           // Every account from the Rust struct
           owner: userWallet.publicKey,
+          authority: userWallet.publicKey,
           fundingAccount: userWSOLAccount,
           transferAuthority: transferAuthorityPDA,
           contract: contractPDA,